@@ -98,7 +98,6 @@ impl<T> LoopListIter<T> {
     }
 
     #[inline]
-    #[allow(dead_code)]
     pub fn len(&self) -> usize {
         self.len
     }
@@ -112,7 +111,6 @@ impl<T> LoopListIter<T> {
     }
 
     #[inline]
-    #[allow(dead_code)]
     pub fn prev(&mut self) {
         if self.node.is_some() {
             let new_current_node = self.node.as_ref().unwrap().borrow().prev.clone();
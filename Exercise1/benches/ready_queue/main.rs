@@ -0,0 +1,97 @@
+// Round robin over large workloads on the previous linked list ready queue and on the ring buffer one which replaced it
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+// Kept as it was before the ring buffer replaced it, only some of its methods are needed here
+#[allow(dead_code)]
+mod loop_list;
+
+use loop_list::LoopListIter;
+use zad1::ring_queue::RingQueue;
+
+const QUANTUM: u64 = 5;
+
+// Bursts and arrival steps, a new process arrives after given number of quanta since the previous one
+fn generate_workload(processes_count: usize, seed: u64) -> Vec<(u64, usize)> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..processes_count).map(|_| (rng.gen_range(1..=300), rng.gen_range(0..3))).collect()
+}
+
+// Every loop iteration is one quantum, returns sum of completion steps so both queues can be checked to do the same work
+fn round_robin_loop_list(workload: &[(u64, usize)]) -> u64 {
+    let mut queue = LoopListIter::new();
+    let mut arrivals = workload.iter();
+    let mut next_arrival = arrivals.next();
+    let mut steps_to_arrival = 0;
+    let mut step = 0;
+    let mut ans = 0;
+    while next_arrival.is_some() || !queue.is_empty() {
+        while let Some((burst, _)) = next_arrival.filter(|_| steps_to_arrival == 0) {
+            queue.add(*burst);
+            next_arrival = arrivals.next();
+            steps_to_arrival = next_arrival.map_or(0, |(_, wait)| *wait);
+        }
+        steps_to_arrival = steps_to_arrival.saturating_sub(1);
+        step += 1;
+        if let Some(node) = queue.get() {
+            let is_finished = {
+                let mut borrow = node.borrow_mut();
+                let time_left = borrow.get_mut();
+                *time_left -= (*time_left).min(QUANTUM);
+                *time_left == 0
+            };
+            drop(node);
+            if is_finished {
+                queue.erase();
+                ans += step;
+            }
+            queue.next();
+        }
+    }
+    ans
+}
+
+fn round_robin_ring_queue(workload: &[(u64, usize)]) -> u64 {
+    let mut queue = RingQueue::new();
+    let mut arrivals = workload.iter();
+    let mut next_arrival = arrivals.next();
+    let mut steps_to_arrival = 0;
+    let mut step = 0;
+    let mut ans = 0;
+    while next_arrival.is_some() || !queue.is_empty() {
+        while let Some((burst, _)) = next_arrival.filter(|_| steps_to_arrival == 0) {
+            queue.add(*burst);
+            next_arrival = arrivals.next();
+            steps_to_arrival = next_arrival.map_or(0, |(_, wait)| *wait);
+        }
+        steps_to_arrival = steps_to_arrival.saturating_sub(1);
+        step += 1;
+        if let Some(time_left) = queue.get_mut() {
+            *time_left -= (*time_left).min(QUANTUM);
+            if *time_left == 0 {
+                queue.erase();
+                ans += step;
+            }
+            queue.next();
+        }
+    }
+    ans
+}
+
+fn ready_queue_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("round_robin");
+    group.sample_size(10);
+    for processes_count in [1_000, 10_000, 100_000] {
+        let workload = generate_workload(processes_count, 42);
+        assert_eq!(round_robin_loop_list(&workload), round_robin_ring_queue(&workload));
+        group.throughput(Throughput::Elements(processes_count as u64));
+        group.bench_with_input(BenchmarkId::new("loop_list", processes_count), &workload, |b, workload| b.iter(|| round_robin_loop_list(workload)));
+        group.bench_with_input(BenchmarkId::new("ring_queue", processes_count), &workload, |b, workload| b.iter(|| round_robin_ring_queue(workload)));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, ready_queue_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,170 @@
+// Scheduler defined outside of zad1 and registered next to the built-in ones, so it's compared with them on the same workload.
+//
+//   cargo run --example custom_scheduler
+
+use zad1::cpu_access_manager::ContextSwitch;
+use zad1::generator::{self, GeneratorSettings};
+use zad1::report::{self, TableFormat};
+use zad1::rotating::Rotating;
+use zad1::{simulator, CpuAccessManager, IoDevice, Process, ProcessDescription, ProcessStatistics, Registry, SimulationError, SimulationSettings, Time, Trace};
+
+// Last come first served without preemption, processor is given to the process which became ready most recently
+#[allow(clippy::upper_case_acronyms)]
+struct LCFS {
+    stack: Vec<Process>,
+    current_process: Option<Process>,
+    is_ending: bool,
+    working_time: Time,
+    waiting_time: Time,
+    total_working_time: Time,
+    total_waiting_time: Time,
+    next_id: u32,
+    current_time: Time,
+    statistics: Vec<ProcessStatistics>,
+    total_required_time: Time,
+    context_switch: ContextSwitch,
+    trace: Trace,
+    io_device: IoDevice<Process>,
+}
+
+impl LCFS {
+    fn new(context_switch_cost: Time) -> Self {
+        Self { stack: Vec::new(), current_process: None, is_ending: false, working_time: 0, waiting_time: 0, total_working_time: 0, total_waiting_time: 0, next_id: 0, current_time: 0, statistics: Vec::new(), total_required_time: 0, context_switch: ContextSwitch::new(context_switch_cost), trace: Trace::new(), io_device: IoDevice::new() }
+    }
+
+    fn return_from_io(&mut self) {
+        for (mut process, time) in self.io_device.advance_to(self.current_time) {
+            process.end_io(time);
+            self.trace.io_completion(time, process.get_id());
+            self.stack.push(process);
+        }
+    }
+}
+
+impl CpuAccessManager for LCFS {
+    fn add_process(&mut self, description: &ProcessDescription) -> Result<u32, SimulationError> {
+        description.validate()?;
+        let total_required_time = self.total_required_time.checked_add(description.get_total_duration()).ok_or(SimulationError::TimeOverflow)?;
+        self.stack.push(Process::new(self.next_id, self.current_time, description));
+        let ans = self.next_id;
+        self.trace.arrival(self.current_time, ans);
+        self.next_id += 1;
+        self.total_required_time = total_required_time;
+        Ok(ans)
+    }
+
+    fn is_working(&self) -> bool {
+        self.current_process.is_some() || !self.stack.is_empty() || self.io_device.is_working()
+    }
+
+    fn simulate_n_ticks(&mut self, mut n: Time) {
+        while n != 0 {
+            self.return_from_io();
+            if self.is_ending {
+                n = n.min(self.total_required_time);
+            }
+            if self.current_process.is_none() {
+                self.current_process = self.stack.pop();
+            }
+            if let Some(mut current) = self.current_process.take() {
+                let switch_time = self.context_switch.switch_to(current.get_id(), self.io_device.limit(self.current_time, n));
+                n -= switch_time;
+                self.current_time += switch_time;
+                if self.context_switch.is_switching() {
+                    self.current_process = Some(current);
+                    continue;
+                }
+                let worked_time = current.work_for(self.current_time, self.io_device.limit(self.current_time, n), current.is_at_burst_start());
+                n -= worked_time;
+                self.trace.run(current.get_id(), self.current_time, worked_time);
+                self.current_time += worked_time;
+                self.total_required_time -= worked_time;
+                self.total_working_time += worked_time;
+                if current.is_finished() {
+                    self.trace.finish(self.current_time, &current);
+                    self.total_required_time -= current.get_total_time_left();
+                    self.statistics.push(current.finalize());
+                }
+                else if current.is_blocked() {
+                    let io_time = current.start_io();
+                    self.trace.block(self.current_time, &current);
+                    self.io_device.block(self.current_time, current, io_time);
+                }
+                else {
+                    self.current_process = Some(current);
+                }
+            }
+            else {
+                let idle_time = self.io_device.limit(self.current_time, n);
+                self.waiting_time = idle_time;
+                self.working_time = 0;
+                self.total_waiting_time += self.waiting_time;
+                self.current_time += idle_time;
+                n -= idle_time;
+            }
+        }
+    }
+
+    fn simulate_till_end_of_every_process(&mut self) {
+        self.is_ending = true;
+        while self.is_working() {
+            self.simulate_n_ticks(self.total_required_time)
+        }
+        self.is_ending = false;
+    }
+
+    fn get_total_working_time(&self) -> Time {
+        self.total_working_time
+    }
+
+    fn get_total_waiting_time(&self) -> Time {
+        self.total_waiting_time
+    }
+
+    fn get_working_time(&self) -> Time {
+        self.working_time
+    }
+
+    fn get_waiting_time(&self) -> Time {
+        self.waiting_time
+    }
+
+    fn get_context_switches_count(&self) -> usize {
+        self.context_switch.get_count()
+    }
+
+    fn get_context_switches_time(&self) -> Time {
+        self.context_switch.get_total_time()
+    }
+
+    fn get_io_utilization(&self) -> f64 {
+        self.io_device.get_working_time() as f64 / self.current_time.max(1) as f64
+    }
+
+    fn enable_trace(&mut self) {
+        self.trace.enable()
+    }
+
+    fn get_trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    fn finalize(mut self: Box<Self>) -> Vec<ProcessStatistics> {
+        self.simulate_till_end_of_every_process();
+        self.statistics
+    }
+}
+
+fn main() -> Result<(), SimulationError> {
+    let mut registry = Registry::new();
+    registry.register("LCFS", |settings| Ok(Box::new(LCFS::new(settings.context_switch_cost))))?;
+    // Parameters which settings don't have are captured by the factory
+    let quant = 2;
+    registry.register("Rotating with quant 2", move |settings| Ok(Box::new(Rotating::new(quant, settings.context_switch_cost))))?;
+
+    let processes = generator::generate(&GeneratorSettings { minimum_processes_count: 200, maximum_processes_count: 200, maximum_io_bursts_count: 2, seed: Some(7), ..GeneratorSettings::default() })?;
+    let settings = SimulationSettings { context_switch_cost: 1, algorithms: Some(["FCFS", "LCFS", "SJF", "Rotating", "Rotating with quant 2"].map(str::to_owned).to_vec()), ..SimulationSettings::default() };
+    let results = simulator::simulate_registered(&registry, processes, &settings)?;
+    print!("{}", report::to_table("workload", &[("generated", results)], TableFormat::Markdown));
+    Ok(())
+}
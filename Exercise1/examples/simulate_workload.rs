@@ -0,0 +1,43 @@
+// Simulation driven from code instead of the menu: workload is built by hand, one scheduler is run step by step
+// and then a few of the built-in ones are compared on the same workload.
+//
+//   cargo run --example simulate_workload
+
+use zad1::{ProcessDescription, Processes, Registry, Simulation, SimulationError, SimulationSettings, Time};
+
+fn main() -> Result<(), SimulationError> {
+    // Time to wait since the previous arrival and description of every process
+    let processes = Processes::from(vec![
+        (0, ProcessDescription::new(30, None, 2)),
+        (2, ProcessDescription::new(5, Some(20), 0)),
+        (3, ProcessDescription::new(12, None, 1).with_io_bursts(vec![(6, 4)])),
+        (10, ProcessDescription::new(8, Some(15), 0)),
+    ]);
+    let settings = SimulationSettings { quant: 4, context_switch_cost: 1, ..SimulationSettings::default() };
+    let registry = Registry::new();
+
+    // Scheduler can be driven directly, processes are added when they arrive
+    let mut cpu = registry.create("Rotating", &settings)?.expect("Rotating is a built-in scheduler");
+    cpu.enable_trace();
+    for (wait, description) in processes.iter() {
+        cpu.simulate_n_ticks(*wait);
+        let id = cpu.add_process(description)?;
+        println!("Process {} arrived, processor has worked for {} ticks so far", id, cpu.get_total_working_time());
+    }
+    cpu.simulate_till_end_of_every_process();
+    println!("{}", cpu.get_trace().to_ascii_gantt(60));
+    for process in cpu.finalize() {
+        let turnaround_time = process.get_turnaround_time().map_or("-".to_owned(), |time: Time| time.to_string());
+        println!("Process {}: turnaround time {}, successful {:?}", process.get_id(), turnaround_time, process.is_successful());
+    }
+
+    // Or whole workload can be simulated at once to get aggregated statistics
+    println!();
+    for name in ["FCFS", "SJF with preemption", "Rotating", "EDF"] {
+        let cpu = registry.create(name, &settings)?.expect("every listed scheduler is built-in");
+        let statistics = Simulation::new(cpu, processes.clone(), false).simulate()?;
+        let deadline_hit_ratio = statistics.get_deadline_hit_ratio().map_or("-".to_owned(), |ratio| format!("{:.0}%", ratio * 100f64));
+        println!("{:<20} average waiting time {:>6.2}, makespan {:>3}, deadlines met {}", name, statistics.get_average_waiting_time(), statistics.get_makespan(), deadline_hit_ratio);
+    }
+    Ok(())
+}
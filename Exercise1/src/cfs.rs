@@ -0,0 +1,279 @@
+use std::collections::BTreeMap;
+
+use crate::cpu_access_manager::{self, CpuAccessManager, FairnessTracker, Process, ProcessDescription, SimulationError, Time};
+use crate::io_device::IoDevice;
+use crate::trace::Trace;
+
+// Weights of nice levels 0..19 taken from Linux, every level gets about 10% less processor time than the previous one
+const NICE_TO_WEIGHT: [u64; 20] = [1024, 820, 655, 526, 423, 335, 272, 215, 172, 137, 110, 87, 70, 56, 45, 36, 29, 23, 18, 15];
+const NICE_0_WEIGHT: u64 = 1024;
+// Virtual runtime is kept in 1/1024 parts of tick, so weighted increments don't lose precision
+const VRUNTIME_SCALE: u64 = 1024;
+
+// Priority of process is used as its nice level, values above 19 are treated as 19
+#[inline]
+fn get_weight(process: &Process) -> u64 {
+    NICE_TO_WEIGHT[process.get_priority().min(19) as usize]
+}
+
+// Virtual runtime grows slower for processes with bigger weight, process with the smallest one runs next
+#[derive(Debug, Clone)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct CFS {
+    target_latency: Time,
+    minimum_granularity: Time,
+    queue: BTreeMap<(u64, u32), Process>,
+    current_process: Option<(Process, u64)>,
+    time_slice: Time,
+    time_slice_left: Time,
+    is_new_call: bool,
+    min_vruntime: u64,
+    is_ending: bool,
+    working_time: Time,
+    waiting_time: Time,
+    total_working_time: Time,
+    total_waiting_time: Time,
+    next_id: u32,
+    current_time: Time,
+    statistics: Vec<cpu_access_manager::ProcessStatistics>,
+    total_required_time: Time,
+    context_switch: cpu_access_manager::ContextSwitch,
+    trace: Trace,
+    io_device: IoDevice<(Process, u64)>,
+    fairness: FairnessTracker,
+}
+
+impl CFS {
+    pub fn new(target_latency: Time, minimum_granularity: Time, context_switch_cost: Time) -> Result<Self, SimulationError> {
+        if target_latency == 0 || minimum_granularity == 0 {
+            return Err(SimulationError::InvalidParameter("Target latency and minimum granularity cannot be 0"));
+        }
+        Ok(Self { target_latency, minimum_granularity, queue: BTreeMap::new(), current_process: None, time_slice: 0, time_slice_left: 0, is_new_call: false, min_vruntime: 0, is_ending: false, working_time: 0, waiting_time: 0, total_working_time: 0, total_waiting_time: 0, next_id: 0, current_time: 0, statistics: Vec::new(), total_required_time: 0, context_switch: cpu_access_manager::ContextSwitch::new(context_switch_cost), trace: Trace::new(), io_device: IoDevice::new(), fairness: FairnessTracker::new() })
+    }
+
+    // Process which has been sleeping gets at most half of target latency of advantage over the ones which were ready, as in Linux
+    fn return_from_io(&mut self) {
+        for ((mut process, vruntime), time) in self.io_device.advance_to(self.current_time) {
+            process.end_io(time);
+            self.trace.io_completion(time, process.get_id());
+            self.fairness.make_ready(process.get_id(), get_weight(&process));
+            let vruntime = vruntime.max(self.min_vruntime.saturating_sub(self.target_latency.saturating_mul(VRUNTIME_SCALE) / 2));
+            self.queue.insert((vruntime, process.get_id()), process);
+        }
+    }
+
+    // Target latency is split between ready processes proportionally to their weights, but no slice is shorter than minimum granularity
+    fn dispatch(&mut self) {
+        if let Some(((vruntime, _), process)) = self.queue.pop_first() {
+            let weight = get_weight(&process);
+            let total_weight = weight + self.queue.values().map(get_weight).sum::<u64>();
+            self.time_slice = (self.target_latency.saturating_mul(weight) / total_weight).max(self.minimum_granularity);
+            self.time_slice_left = self.time_slice;
+            self.is_new_call = true;
+            self.current_process = Some((process, vruntime));
+        }
+    }
+
+    fn update_min_vruntime(&mut self) {
+        let leftmost = self.queue.keys().next().map(|(vruntime, _)| *vruntime);
+        let current = self.current_process.as_ref().map(|(_, vruntime)| *vruntime);
+        if let Some(vruntime) = [leftmost, current].into_iter().flatten().min() {
+            self.min_vruntime = self.min_vruntime.max(vruntime);
+        }
+    }
+}
+
+impl CpuAccessManager for CFS {
+    // New process starts with the smallest virtual runtime, so it doesn't get processor for the whole time which passed before its arrival
+    fn add_process(&mut self, description: &ProcessDescription) -> Result<u32, SimulationError> {
+        description.validate()?;
+        let total_required_time = self.total_required_time.checked_add(description.get_total_duration()).ok_or(SimulationError::TimeOverflow)?;
+        let process = Process::new(self.next_id, self.current_time, description);
+        self.fairness.make_ready(self.next_id, get_weight(&process));
+        self.queue.insert((self.min_vruntime, self.next_id), process);
+        let ans = self.next_id;
+        self.trace.arrival(self.current_time, ans);
+        self.next_id += 1;
+        self.total_required_time = total_required_time;
+        Ok(ans)
+    }
+
+    #[inline]
+    fn is_working(&self) -> bool {
+        self.current_process.is_some() || !self.queue.is_empty() || self.io_device.is_working()
+    }
+
+    #[inline]
+    fn simulate_one_tick(&mut self) {
+        self.simulate_n_ticks(1)
+    }
+
+    fn simulate_n_ticks(&mut self, mut n: Time) {
+        while n != 0 {
+            self.return_from_io();
+            if self.is_ending {
+                n = n.min(self.total_required_time);
+            }
+            if self.current_process.is_none() {
+                self.dispatch();
+            }
+            if let Some((mut current, mut vruntime)) = self.current_process.take() {
+                let switch_time = self.context_switch.switch_to(current.get_id(), self.io_device.limit(self.current_time, n));
+                n -= switch_time;
+                self.current_time += switch_time;
+                if self.context_switch.is_switching() {
+                    self.current_process = Some((current, vruntime));
+                    continue;
+                }
+                let worked_time = current.work_for(self.current_time, self.io_device.limit(self.current_time, n).min(self.time_slice_left), self.is_new_call);
+                if worked_time != 0 {
+                    self.is_new_call = false;
+                }
+                n -= worked_time;
+                // Charged for the whole part of slice used so far, so rounding doesn't depend on how the slice was split into calls
+                let charge = |used: Time| used.saturating_mul(NICE_0_WEIGHT * VRUNTIME_SCALE) / get_weight(&current);
+                let used_time = self.time_slice - self.time_slice_left;
+                vruntime += charge(used_time + worked_time) - charge(used_time);
+                self.time_slice_left -= worked_time;
+                self.trace.run(current.get_id(), self.current_time, worked_time);
+                self.fairness.run(current.get_id(), worked_time);
+                self.current_time += worked_time;
+                self.total_required_time -= worked_time;
+                self.total_working_time += worked_time;
+                // Minimum includes the time just used also when process leaves the processor, so it doesn't depend on how simulation is split into calls
+                self.min_vruntime = self.min_vruntime.max(self.queue.keys().next().map_or(vruntime, |(leftmost, _)| vruntime.min(*leftmost)));
+                if current.is_finished() {
+                    self.trace.finish(self.current_time, &current);
+                    self.fairness.finish(current.get_id());
+                    self.total_required_time -= current.get_total_time_left();
+                    self.statistics.push(current.finalize());
+                }
+                else if current.is_blocked() {
+                    let io_time = current.start_io();
+                    self.trace.block(self.current_time, &current);
+                    self.fairness.leave(current.get_id());
+                    self.io_device.block(self.current_time, (current, vruntime), io_time);
+                }
+                else if self.time_slice_left == 0 {
+                    self.queue.insert((vruntime, current.get_id()), current);
+                }
+                else {
+                    self.current_process = Some((current, vruntime));
+                }
+                self.update_min_vruntime();
+            }
+            else {
+                let idle_time = self.io_device.limit(self.current_time, n);
+                self.waiting_time = idle_time;
+                self.working_time = 0;
+                self.total_waiting_time += self.waiting_time;
+                self.current_time += idle_time;
+                n -= idle_time;
+            }
+        }
+    }
+
+    #[inline]
+    fn get_total_working_time(&self) -> Time {
+        self.total_working_time
+    }
+
+    #[inline]
+    fn get_total_waiting_time(&self) -> Time {
+        self.total_waiting_time
+    }
+
+    #[inline]
+    fn get_working_time(&self) -> Time {
+        self.working_time
+    }
+
+    #[inline]
+    fn get_waiting_time(&self) -> Time {
+        self.waiting_time
+    }
+
+    #[inline]
+    fn get_context_switches_count(&self) -> usize {
+        self.context_switch.get_count()
+    }
+
+    #[inline]
+    fn get_context_switches_time(&self) -> Time {
+        self.context_switch.get_total_time()
+    }
+
+    #[inline]
+    fn get_io_utilization(&self) -> f64 {
+        self.io_device.get_working_time() as f64 / self.current_time.max(1) as f64
+    }
+
+    #[inline]
+    fn get_fairness_deviation(&self) -> Option<f64> {
+        Some(self.fairness.get_deviation())
+    }
+
+    #[inline]
+    fn get_processes_shares(&self) -> Vec<cpu_access_manager::ProcessShare> {
+        self.fairness.get_shares().to_vec()
+    }
+
+    #[inline]
+    fn enable_trace(&mut self) {
+        self.trace.enable()
+    }
+
+    #[inline]
+    fn get_trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    #[inline]
+    fn simulate_till_end_of_every_process(&mut self) {
+        self.is_ending = true;
+        while self.is_working() {
+            self.simulate_n_ticks(self.total_required_time)
+        }
+        self.is_ending = false;
+    }
+
+    #[inline]
+    fn finalize(mut self: Box<Self>) -> Vec<cpu_access_manager::ProcessStatistics> {
+        self.simulate_till_end_of_every_process();
+        self.statistics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn simulate(descriptions: &[ProcessDescription]) -> CFS {
+        let mut cpu = CFS::new(20, 4, 0).unwrap();
+        for description in descriptions {
+            cpu.add_process(description).unwrap();
+        }
+        cpu.simulate_till_end_of_every_process();
+        cpu
+    }
+
+    // Bursts are proportional to weights of nice levels 0 and 5, so with exact weighting both processes finish together
+    #[test]
+    fn processor_time_follows_weights() {
+        let cpu = simulate(&[ProcessDescription::new(2048, None, 0), ProcessDescription::new(670, None, 5)]);
+        assert_eq!(cpu.get_processes_shares().len(), 2);
+        for share in cpu.get_processes_shares() {
+            let expected = share.get_weight() as f64 / (1024 + 335) as f64;
+            assert!((share.get_achieved_share() - expected).abs() < 0.01, "{:?}", share);
+        }
+        assert!(cpu.statistics.iter().all(|process| process.get_completion_time().unwrap().abs_diff(2718) <= 20));
+        assert!(cpu.get_fairness_deviation().unwrap() < 0.01);
+    }
+
+    #[test]
+    fn equal_weights_share_processor_equally() {
+        let cpu = simulate(&[ProcessDescription::new(1000, None, 3), ProcessDescription::new(1000, None, 3), ProcessDescription::new(1000, None, 3)]);
+        assert!(cpu.get_fairness_deviation().unwrap() < 0.01, "{:?}", cpu.get_fairness_deviation());
+        assert!(cpu.get_processes_shares().iter().all(|share| (share.get_achieved_share() - 1f64 / 3f64).abs() < 0.01));
+    }
+}
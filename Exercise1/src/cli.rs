@@ -0,0 +1,442 @@
+// Non-interactive command line for scripts and batch runs, without subcommand the interactive menu is shown.
+// Results go to stdout (or to --output file), messages about saved files and errors go to stderr,
+// so the output can be redirected or piped, failures end with non-zero exit code.
+//
+// Examples:
+//   zad1 random --seed 42 --max-processes 200 --inter-arrival exponential:20 --format csv
+//   zad1 manual -p 0,25 -p 3,10,40,1 --algorithms fcfs,sjf
+//   zad1 file workload.csv --quant 10 --context-switch-cost 1 --format markdown --output results.md
+//   zad1 sweep --workload workload.csv --min-quant 1 --max-quant 20
+//   zad1 experiment --runs 50 --seed 7 --job-class 0.8:exponential:20 --job-class 0.2:pareto:100:1.5
+//   zad1 random --groups 3 --group-shares 2,1,1 --group-policy sjf --algorithms "fair share",stride
+//   zad1 periodic --task 10:3 --task 15:4:12 --assignment deadline-monotonic
+//   zad1 visualize --algorithm mlfq --workload workload.csv --mlfq-quanta 4,8,16
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use zad1::cpu_access_manager::{SimulationError, Time};
+use zad1::generator::{self, Distribution, GeneratorSettings, JobClass};
+use zad1::periodic::{self, PeriodicTask, PriorityAssignment};
+use zad1::report::{self, TableFormat};
+use zad1::simulator::{self, Processes, SimulationSettings};
+use zad1::workload::{self, WorkloadError};
+use zad1::{experiment, fair_share, schedulability};
+use zad1::registry::Registry;
+
+use crate::visualizer;
+
+#[derive(Debug)]
+pub enum CliError {
+    Simulation(SimulationError),
+    Workload(WorkloadError),
+    Io(std::io::Error),
+    InvalidArgument(String),
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Simulation(err) => write!(f, "{}", err),
+            CliError::Workload(err) => write!(f, "Workload file: {}", err),
+            CliError::Io(err) => write!(f, "{}", err),
+            CliError::InvalidArgument(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<SimulationError> for CliError {
+    fn from(value: SimulationError) -> Self {
+        CliError::Simulation(value)
+    }
+}
+
+impl From<WorkloadError> for CliError {
+    fn from(value: WorkloadError) -> Self {
+        CliError::Workload(value)
+    }
+}
+
+impl From<std::io::Error> for CliError {
+    fn from(value: std::io::Error) -> Self {
+        CliError::Io(value)
+    }
+}
+
+#[derive(Debug, Parser)]
+#[command(name = "zad1", version, about = "CPU scheduling simulator, interactive menu is shown if no command is given")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    #[command(flatten)]
+    pub simulation: SimulationArgs,
+    #[command(flatten)]
+    pub output: OutputArgs,
+    #[arg(long, value_name = "FILE", hide = true, help = "The same as the file command, kept for older scripts")]
+    pub replay: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    #[command(about = "Simulate randomly generated workload")]
+    Random(GeneratorArgs),
+    #[command(about = "Simulate processes given in command line")]
+    Manual {
+        #[arg(short, long = "process", value_name = "ROW", required = true, help = "Process as a row of workload CSV file: wait,duration[,lifetime[,priority[,io_bursts[,tickets[,group[,period]]]]]]")]
+        processes: Vec<String>,
+    },
+    #[command(about = "Simulate workload file (.csv or .json)")]
+    File {
+        #[arg(help = "Workload file, see workload.rs for its format")]
+        path: String,
+    },
+    #[command(about = "Compare quantum based algorithms across quantum values")]
+    Sweep(SweepArgs),
+    #[command(about = "Simulate many random workloads and summarize them with confidence intervals")]
+    Experiment(ExperimentArgs),
+    #[command(about = "Analyze schedulability of periodic task set and check it with simulation")]
+    Periodic(PeriodicArgs),
+    #[command(about = "Step through simulation of one algorithm in terminal user interface")]
+    Visualize(VisualizeArgs),
+}
+
+// Every parameter which can be changed in the interactive menu, missing ones keep their defaults
+#[derive(Debug, Args)]
+pub struct SimulationArgs {
+    #[arg(long, global = true, help = "Time quant of quantum based algorithms")]
+    quant: Option<Time>,
+    #[arg(long, global = true, value_delimiter = ',', value_name = "QUANTA", help = "Quant of every MLFQ level, e.g. 5,10,20")]
+    mlfq_quanta: Option<Vec<Time>>,
+    #[arg(long, global = true, value_name = "PERIOD", help = "MLFQ priority boost period (0 - no boost)")]
+    mlfq_boost_period: Option<Time>,
+    #[arg(long, global = true, value_name = "PERIOD", help = "Priority aging period (0 - no aging)")]
+    aging_period: Option<Time>,
+    #[arg(long, global = true, value_name = "COST", help = "Time of every context switch")]
+    context_switch_cost: Option<Time>,
+    #[arg(long, global = true, value_name = "DIRECTORY", help = "Export trace of every algorithm to directory")]
+    trace_directory: Option<String>,
+    #[arg(long, global = true, value_name = "FILE", help = "Save generated workload to file")]
+    save_workload: Option<String>,
+    #[arg(long, global = true, value_name = "COUNT", help = "Cores count of multiprocessor algorithms")]
+    cores: Option<usize>,
+    #[arg(long, global = true, value_name = "PERIOD", help = "Load balance period of per-core queues")]
+    load_balance_period: Option<Time>,
+    #[arg(long, global = true, value_name = "ALPHA", help = "Burst prediction alpha [0;1]")]
+    prediction_alpha: Option<f64>,
+    #[arg(long, global = true, value_name = "ESTIMATE", help = "Burst estimate of new process, it changes only after a burst ends, so processes without I/O bursts are ordered like in FCFS")]
+    initial_burst_estimate: Option<f64>,
+    #[arg(long, global = true, value_name = "LATENCY", help = "CFS target latency")]
+    cfs_target_latency: Option<Time>,
+    #[arg(long, global = true, value_name = "GRANULARITY", help = "CFS minimum granularity")]
+    cfs_minimum_granularity: Option<Time>,
+    #[arg(long, global = true, value_name = "SEED", help = "Lottery seed (random if omitted)")]
+    lottery_seed: Option<u64>,
+    #[arg(long, global = true, help = "Abandon processes which can't meet their deadlines")]
+    abandon_hopeless: bool,
+    #[arg(long, global = true, value_name = "QUANT", help = "Minimum quant of adaptive round robin")]
+    adaptive_min_quant: Option<Time>,
+    #[arg(long, global = true, value_name = "QUANT", help = "Maximum quant of adaptive round robin")]
+    adaptive_max_quant: Option<Time>,
+    #[arg(long, global = true, value_name = "LATENCY", help = "Time in which adaptive round robin runs every ready process once")]
+    adaptive_target_latency: Option<Time>,
+    #[arg(long, global = true, value_delimiter = ',', value_name = "SHARES", help = "Fair share of every group by its number, e.g. 3,1 (the other groups get 1)")]
+    group_shares: Option<Vec<u32>>,
+    #[arg(long, global = true, value_enum, value_name = "POLICY", help = "Fair share policy inside group")]
+    group_policy: Option<GroupPolicyArg>,
+    #[arg(long, global = true, value_delimiter = ',', value_name = "NAMES", help = "Simulate only given algorithms, e.g. FCFS,SJF,\"Rate monotonic\" (case insensitive)")]
+    algorithms: Option<Vec<String>>,
+}
+
+impl SimulationArgs {
+    pub fn apply(&self, settings: &mut SimulationSettings) -> Result<(), CliError> {
+        if let Some(algorithms) = &self.algorithms {
+            let registry = Registry::new();
+            if let Some(unknown) = algorithms.iter().find(|algorithm| registry.find(algorithm).is_none()) {
+                return Err(CliError::InvalidArgument(format!("Unknown algorithm: {} (known algorithms: {})", unknown, registry.get_names().join(", "))));
+            }
+            settings.algorithms = Some(algorithms.iter().map(|algorithm| algorithm.trim().to_owned()).collect());
+        }
+        settings.quant = self.quant.unwrap_or(settings.quant);
+        settings.mlfq_quantum_times = self.mlfq_quanta.clone().unwrap_or(settings.mlfq_quantum_times.clone());
+        settings.mlfq_boost_period = self.mlfq_boost_period.map_or(settings.mlfq_boost_period, |period| Some(period).filter(|period| *period != 0));
+        settings.aging_period = self.aging_period.map_or(settings.aging_period, |period| Some(period).filter(|period| *period != 0));
+        settings.context_switch_cost = self.context_switch_cost.unwrap_or(settings.context_switch_cost);
+        settings.trace_directory = self.trace_directory.clone().or(settings.trace_directory.clone());
+        settings.workload_output = self.save_workload.clone().or(settings.workload_output.clone());
+        settings.cores_count = self.cores.unwrap_or(settings.cores_count);
+        settings.load_balance_period = self.load_balance_period.unwrap_or(settings.load_balance_period);
+        settings.prediction_alpha = self.prediction_alpha.unwrap_or(settings.prediction_alpha);
+        settings.initial_burst_estimate = self.initial_burst_estimate.unwrap_or(settings.initial_burst_estimate);
+        settings.cfs_target_latency = self.cfs_target_latency.unwrap_or(settings.cfs_target_latency);
+        settings.cfs_minimum_granularity = self.cfs_minimum_granularity.unwrap_or(settings.cfs_minimum_granularity);
+        settings.lottery_seed = self.lottery_seed.or(settings.lottery_seed);
+        settings.abandon_hopeless_processes |= self.abandon_hopeless;
+        settings.adaptive_minimum_quant = self.adaptive_min_quant.unwrap_or(settings.adaptive_minimum_quant);
+        settings.adaptive_maximum_quant = self.adaptive_max_quant.unwrap_or(settings.adaptive_maximum_quant);
+        settings.adaptive_target_latency = self.adaptive_target_latency.unwrap_or(settings.adaptive_target_latency);
+        settings.group_shares = self.group_shares.clone().unwrap_or(settings.group_shares.clone());
+        settings.group_policy = match self.group_policy {
+            Some(GroupPolicyArg::RoundRobin) => fair_share::GroupPolicy::RoundRobin,
+            Some(GroupPolicyArg::Fcfs) => fair_share::GroupPolicy::FCFS,
+            Some(GroupPolicyArg::Sjf) => fair_share::GroupPolicy::SJF,
+            Some(GroupPolicyArg::Priority) => fair_share::GroupPolicy::Priority,
+            None => settings.group_policy,
+        };
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GroupPolicyArg {
+    #[value(alias = "rr")]
+    RoundRobin,
+    Fcfs,
+    Sjf,
+    Priority,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Csv,
+    Markdown,
+}
+
+#[derive(Debug, Args)]
+pub struct OutputArgs {
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text, help = "Format of results, text tables are printed as CSV")]
+    format: OutputFormat,
+    #[arg(long, global = true, value_name = "FILE", help = "Save table to file instead of printing it")]
+    output: Option<String>,
+}
+
+impl OutputArgs {
+    #[inline]
+    fn get_table_format(&self) -> TableFormat {
+        match self.format {
+            OutputFormat::Markdown => TableFormat::Markdown,
+            OutputFormat::Text | OutputFormat::Csv => TableFormat::Csv,
+        }
+    }
+
+    fn write(&self, table: String, output: Option<&str>) -> Result<(), CliError> {
+        match output {
+            Some(path) => {
+                std::fs::write(path, table)?;
+                eprintln!("Table saved to {}", path);
+            },
+            None => print!("{}", table),
+        }
+        Ok(())
+    }
+}
+
+// Parameters of the workload generator, missing ones keep their defaults
+#[derive(Debug, Args)]
+pub struct GeneratorArgs {
+    #[arg(long, value_name = "COUNT", help = "Minimum number of processes")]
+    min_processes: Option<usize>,
+    #[arg(long, value_name = "COUNT", help = "Maximum number of processes")]
+    max_processes: Option<usize>,
+    #[arg(long, value_name = "DISTRIBUTION", help = "Time between new processes, e.g. uniform:0:100, exponential:50, pareto:10:1.5, bimodal:10:200:0.2")]
+    inter_arrival: Option<Distribution>,
+    #[arg(long, value_name = "SHARE:DISTRIBUTION", help = "Job class with its share and distribution of process duration, e.g. 0.8:exponential:20, can be repeated")]
+    job_class: Vec<JobClass>,
+    #[arg(long, value_name = "PROBABILITY", help = "Probability that process has lifetime [0;1]")]
+    lifetime_probability: Option<f64>,
+    #[arg(long, value_name = "DISTRIBUTION", help = "Lifetime of process which has it")]
+    lifetime: Option<Distribution>,
+    #[arg(long, value_name = "COUNT", help = "Priority classes count")]
+    priority_classes: Option<u32>,
+    #[arg(long, value_name = "COUNT", help = "Minimum tickets count")]
+    min_tickets: Option<u32>,
+    #[arg(long, value_name = "COUNT", help = "Maximum tickets count")]
+    max_tickets: Option<u32>,
+    #[arg(long, value_name = "COUNT", help = "Maximum I/O bursts count per process")]
+    max_io_bursts: Option<usize>,
+    #[arg(long, value_name = "DISTRIBUTION", help = "I/O burst duration")]
+    io_duration: Option<Distribution>,
+    #[arg(long, value_name = "COUNT", help = "Groups count, every process is owned by random one")]
+    groups: Option<u32>,
+    #[arg(long, help = "Seed of the generator (random if omitted)")]
+    seed: Option<u64>,
+}
+
+impl GeneratorArgs {
+    fn to_settings(&self) -> GeneratorSettings {
+        // Default minimum is lowered to the given maximum, so it's enough to give only the maximum
+        let default = GeneratorSettings::default();
+        let maximum_processes_count = self.max_processes.unwrap_or(default.maximum_processes_count);
+        let maximum_tickets = self.max_tickets.unwrap_or(default.maximum_tickets);
+        GeneratorSettings {
+            minimum_processes_count: self.min_processes.unwrap_or(default.minimum_processes_count.min(maximum_processes_count)),
+            maximum_processes_count,
+            inter_arrival_time: self.inter_arrival.clone().unwrap_or(default.inter_arrival_time),
+            job_classes: match self.job_class.is_empty() {
+                true => default.job_classes,
+                false => self.job_class.clone(),
+            },
+            lifetime_probability: self.lifetime_probability.unwrap_or(default.lifetime_probability),
+            lifetime: self.lifetime.clone().unwrap_or(default.lifetime),
+            priority_classes_count: self.priority_classes.unwrap_or(default.priority_classes_count),
+            minimum_tickets: self.min_tickets.unwrap_or(default.minimum_tickets.min(maximum_tickets)),
+            maximum_tickets,
+            maximum_io_bursts_count: self.max_io_bursts.unwrap_or(default.maximum_io_bursts_count),
+            io_duration: self.io_duration.clone().unwrap_or(default.io_duration),
+            groups_count: self.groups.unwrap_or(default.groups_count),
+            seed: self.seed,
+        }
+    }
+
+    fn generate(&self, settings: &SimulationSettings) -> Result<Processes, CliError> {
+        let processes = generator::generate(&self.to_settings())?;
+        if let Some(path) = &settings.workload_output {
+            workload::save(&processes, path)?;
+            eprintln!("Workload saved to {}", path);
+        }
+        Ok(processes)
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct SweepArgs {
+    #[arg(long, value_name = "FILE", help = "Workload file, random workload is generated if omitted")]
+    workload: Option<String>,
+    #[arg(long, default_value_t = 1, help = "Minimum quant")]
+    min_quant: Time,
+    #[arg(long, default_value_t = 50, help = "Maximum quant")]
+    max_quant: Time,
+    #[arg(long, default_value_t = 1, help = "Quant step")]
+    quant_step: usize,
+    #[command(flatten)]
+    generator: GeneratorArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct VisualizeArgs {
+    #[arg(short, long, help = "Algorithm to step through, e.g. 'SJF with preemption'")]
+    algorithm: String,
+    #[arg(long, value_name = "FILE", help = "Workload file, random workload is generated if omitted")]
+    workload: Option<String>,
+    #[command(flatten)]
+    generator: GeneratorArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct ExperimentArgs {
+    #[arg(long, default_value_t = 30, help = "Runs count")]
+    runs: usize,
+    #[arg(long, help = "Metric of pairwise comparison (default - the second metric of summary)")]
+    metric: Option<String>,
+    #[arg(long, value_name = "FILE", help = "Save pairwise comparison to file instead of printing it")]
+    pairwise_output: Option<String>,
+    #[command(flatten)]
+    generator: GeneratorArgs,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum AssignmentArg {
+    #[value(alias = "rm")]
+    RateMonotonic,
+    #[value(alias = "dm")]
+    DeadlineMonotonic,
+}
+
+#[derive(Debug, Args)]
+pub struct PeriodicArgs {
+    #[arg(short, long = "task", value_name = "PERIOD:WCET[:DEADLINE]", required = true, help = "Periodic task, relative deadline equals period if omitted, can be repeated")]
+    tasks: Vec<PeriodicTask>,
+    #[arg(long, value_enum, default_value_t = AssignmentArg::RateMonotonic, help = "Priority assignment")]
+    assignment: AssignmentArg,
+    #[arg(long, help = "Simulation horizon (default - hyperperiod)")]
+    horizon: Option<Time>,
+}
+
+fn simulate(processes: Processes, settings: &SimulationSettings, output: &OutputArgs) -> Result<(), CliError> {
+    let results = simulator::simulate_every(processes, settings)?;
+    if let Some(directory) = &settings.trace_directory {
+        crate::export_traces(&results, directory)?;
+        eprintln!("Traces saved to {}", directory);
+    }
+    match output.format {
+        OutputFormat::Text if output.output.is_some() => return Err(CliError::InvalidArgument("Text report can only be printed, use csv or markdown format to save results".to_owned())),
+        OutputFormat::Text => crate::print_results(results),
+        _ => output.write(report::to_table("quant", &[(settings.quant, results)], output.get_table_format()), output.output.as_deref())?,
+    }
+    Ok(())
+}
+
+pub fn run(command: Command, settings: &SimulationSettings, output: &OutputArgs) -> Result<(), CliError> {
+    match command {
+        Command::Random(generator_args) => simulate(generator_args.generate(settings)?, settings, output),
+        Command::Manual { processes } => simulate(workload::from_csv(&processes.join("\n"))?, settings, output),
+        Command::File { path } => simulate(workload::load(path)?, settings, output),
+        Command::Sweep(sweep_args) => {
+            if sweep_args.min_quant == 0 || sweep_args.min_quant > sweep_args.max_quant || sweep_args.quant_step == 0 {
+                return Err(CliError::InvalidArgument("Quant range has to be non-empty, start above 0 and have positive step".to_owned()));
+            }
+            let processes = match &sweep_args.workload {
+                Some(path) => workload::load(path)?,
+                None => sweep_args.generator.generate(settings)?,
+            };
+            let results = simulator::sweep_quantum(processes, settings, (sweep_args.min_quant..=sweep_args.max_quant).step_by(sweep_args.quant_step))?;
+            output.write(report::to_table("quant", &results, output.get_table_format()), output.output.as_deref())
+        },
+        Command::Experiment(experiment_args) => {
+            let results = experiment::run_experiment(&experiment_args.generator.to_settings(), settings, experiment_args.runs.max(1))?;
+            let metric = match &experiment_args.metric {
+                Some(metric) => results.get_metrics().iter().position(|name| name.eq_ignore_ascii_case(metric)).ok_or(CliError::InvalidArgument(format!("Unknown metric: {} (known metrics: {})", metric, results.get_metrics().join(", "))))?,
+                None => 1,
+            };
+            eprintln!("Base seed: {}, runs: {}", results.get_base_seed(), results.get_runs_count());
+            output.write(results.to_summary_table(output.get_table_format()), output.output.as_deref())?;
+            output.write(results.to_pairwise_table(metric, output.get_table_format()), experiment_args.pairwise_output.as_deref())
+        },
+        Command::Periodic(periodic_args) => {
+            let assignment = match periodic_args.assignment {
+                AssignmentArg::RateMonotonic => PriorityAssignment::RateMonotonic,
+                AssignmentArg::DeadlineMonotonic => PriorityAssignment::DeadlineMonotonic,
+            };
+            let horizon = match periodic_args.horizon {
+                Some(horizon) => horizon.max(1),
+                None => periodic::get_hyperperiod(&periodic_args.tasks)?,
+            };
+            let report = schedulability::analyze(&periodic_args.tasks, assignment, horizon)?;
+            if output.format == OutputFormat::Text {
+                crate::print_schedulability_summary(&report);
+            }
+            output.write(report.to_table(output.get_table_format()), output.output.as_deref())
+        },
+        Command::Visualize(visualize_args) => {
+            let registry = Registry::new();
+            let name = registry.find(&visualize_args.algorithm).ok_or(CliError::InvalidArgument(format!("Unknown algorithm: {} (known algorithms: {})", visualize_args.algorithm, registry.get_names().join(", "))))?;
+            let processes = match &visualize_args.workload {
+                Some(path) => workload::load(path)?,
+                None => visualize_args.generator.generate(settings)?,
+            };
+            let cpu = registry.create(name, settings)?.ok_or(SimulationError::InvalidParameter("Unknown algorithm"))?;
+            visualizer::run(name, visualizer::Stepper::new(cpu, processes)?)?;
+            Ok(())
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn command_line_is_parsed() {
+        Cli::command().debug_assert();
+        let cli = Cli::try_parse_from(["zad1", "random", "--seed", "3", "--job-class", "0.8:exponential:20", "--job-class", "0.2:pareto:100:1.5", "--quant", "7", "--mlfq-boost-period", "0", "--algorithms", "FCFS,SJF"]).unwrap();
+        let mut settings = SimulationSettings::default();
+        cli.simulation.apply(&mut settings).unwrap();
+        assert_eq!((settings.quant, settings.mlfq_boost_period, settings.algorithms), (7, None, Some(vec!["FCFS".to_owned(), "SJF".to_owned()])));
+        assert!(matches!(cli.command, Some(Command::Random(_))));
+        assert!(Cli::try_parse_from(["zad1", "random", "--inter-arrival", "uniform:10:1"]).is_err());
+        assert!(Cli::try_parse_from(["zad1", "periodic", "--task", "10:3:2"]).is_err());
+        let cli = Cli::try_parse_from(["zad1", "--algorithms", "FIFO"]).unwrap();
+        assert!(cli.simulation.apply(&mut SimulationSettings::default()).is_err());
+    }
+}
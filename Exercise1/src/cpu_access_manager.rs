@@ -1,16 +1,152 @@
+// Every simulated time and duration, 64 bits so long and dense workloads don't overflow
+pub type Time = u64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimulationError {
+    InvalidProcess(&'static str),
+    InvalidParameter(&'static str),
+    TimeOverflow,
+}
+
+impl std::fmt::Display for SimulationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SimulationError::InvalidProcess(message) | SimulationError::InvalidParameter(message) => write!(f, "{}", message),
+            SimulationError::TimeOverflow => write!(f, "Simulated time doesn't fit in {} bits", Time::BITS),
+        }
+    }
+}
+
+impl std::error::Error for SimulationError {}
+
+#[derive(Clone, Debug)]
+pub struct ProcessDescription {
+    duration: Time, // First processor burst
+    lifetime: Option<Time>,
+    priority: u32, // 0 - the most important
+    io_bursts: Vec<(Time, Time)>, // I/O burst and processor burst which follows it
+    tickets: u32, // Requested share of processor for proportional share schedulers
+    period: Option<Time>, // Only for jobs of periodic tasks, used by rate monotonic scheduling
+    group: u32, // Owner of the process (e.g. user), fair share scheduling divides processor among groups
+}
+
+pub const DEFAULT_TICKETS: u32 = 100;
+
+impl ProcessDescription {
+    #[inline]
+    pub fn new(duration: Time, lifetime: Option<Time>, priority: u32) -> Self {
+        Self { duration, lifetime, priority, io_bursts: Vec::new(), tickets: DEFAULT_TICKETS, period: None, group: 0 }
+    }
+
+    #[inline]
+    pub fn with_io_bursts(mut self, io_bursts: Vec<(Time, Time)>) -> Self {
+        self.io_bursts = io_bursts;
+        self
+    }
+
+    #[inline]
+    pub fn with_tickets(mut self, tickets: u32) -> Self {
+        self.tickets = tickets;
+        self
+    }
+
+    #[inline]
+    pub fn with_period(mut self, period: Time) -> Self {
+        self.period = Some(period);
+        self
+    }
+
+    #[inline]
+    pub fn with_group(mut self, group: u32) -> Self {
+        self.group = group;
+        self
+    }
+
+    #[inline]
+    pub fn get_duration(&self) -> Time {
+        self.duration
+    }
+
+    #[inline]
+    pub fn get_io_bursts(&self) -> &[(Time, Time)] {
+        &self.io_bursts
+    }
+
+    // Processor time of every burst together, it fits in Time for every valid description
+    #[inline]
+    pub fn get_total_duration(&self) -> Time {
+        self.duration + self.io_bursts.iter().map(|(_, duration)| duration).sum::<Time>()
+    }
+
+    // Processor and I/O time of every burst together, None if it doesn't fit in Time
+    pub fn get_checked_total_time(&self) -> Option<Time> {
+        self.io_bursts.iter().try_fold(self.duration, |total, (io_time, duration)| total.checked_add(*io_time)?.checked_add(*duration))
+    }
+
+    pub fn validate(&self) -> Result<(), SimulationError> {
+        if self.duration == 0 || self.io_bursts.iter().any(|(_, duration)| *duration == 0) {
+            return Err(SimulationError::InvalidProcess("Process can't have duration time of 0"));
+        }
+        if self.io_bursts.iter().any(|(io_time, _)| *io_time == 0) {
+            return Err(SimulationError::InvalidProcess("Process can't have I/O burst of 0"));
+        }
+        if self.tickets == 0 {
+            return Err(SimulationError::InvalidProcess("Process can't have 0 tickets"));
+        }
+        if self.period == Some(0) {
+            return Err(SimulationError::InvalidProcess("Process can't have period of 0"));
+        }
+        self.get_checked_total_time().map(|_| ()).ok_or(SimulationError::TimeOverflow)
+    }
+
+    #[inline]
+    pub fn get_lifetime(&self) -> Option<Time> {
+        self.lifetime
+    }
+
+    #[inline]
+    pub fn get_priority(&self) -> u32 {
+        self.priority
+    }
+
+    #[inline]
+    pub fn get_tickets(&self) -> u32 {
+        self.tickets
+    }
+
+    #[inline]
+    pub fn get_period(&self) -> Option<Time> {
+        self.period
+    }
+
+    #[inline]
+    pub fn get_group(&self) -> u32 {
+        self.group
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct Process {
     id: u32,
-    initial_time: u32,
-    time_left: u32,
-    creation_time: u32,
-    completion_time: Option<u32>,
+    initial_time: Time,
+    time_left: Time,
+    creation_time: Time,
+    completion_time: Option<Time>,
     call_count: usize,
     partial_waiting_time: f64,
-    last_time_with_access: u32,
-    lifetime: Option<u32>,
+    last_time_with_access: Time,
+    lifetime: Option<Time>,
+    priority: u32,
     finished_state: Option<bool>, // None - unfinished, true - successful, false - unsuccessful
+    burst_time: Time,
+    io_bursts: std::collections::VecDeque<(Time, Time)>,
+    pending_io: Option<Time>,
+    blocked_since: Time,
+    io_time: Time,
+    tickets: u32,
+    first_run_time: Option<Time>,
+    period: Option<Time>,
+    group: u32,
 }
 
 #[derive(Debug, Clone)]
@@ -20,29 +156,27 @@ pub struct ProcessStatistics {
 
 impl ProcessStatistics {
     #[inline]
-    #[allow(dead_code)]
     pub fn get_id(&self) -> u32 {
         self.process.get_id()
     }
 
     #[inline]
-    pub fn get_initial_time(&self) -> u32 {
+    pub fn get_initial_time(&self) -> Time {
         self.process.get_initial_time()
     }
 
     #[inline]
-    #[allow(dead_code)]
-    pub fn get_time_left(&self) -> u32 {
+    pub fn get_time_left(&self) -> Time {
         self.process.get_time_left()
     }
 
     #[inline]
-    pub fn get_creation_time(&self) -> u32 {
+    pub fn get_creation_time(&self) -> Time {
         self.process.get_creation_time()
     }
 
     #[inline]
-    pub fn get_completion_time(&self) -> Option<u32> {
+    pub fn get_completion_time(&self) -> Option<Time> {
         self.process.get_completion_time()
     }
 
@@ -57,7 +191,6 @@ impl ProcessStatistics {
     }
 
     #[inline]
-    #[allow(dead_code)]
     pub fn is_finished(&self) -> bool {
         self.process.is_finished()
     }
@@ -73,26 +206,68 @@ impl ProcessStatistics {
     }
 
     #[inline]
-    #[allow(dead_code)]
-    pub fn get_lifetime(&self) -> Option<u32> {
+    pub fn get_lifetime(&self) -> Option<Time> {
         self.process.get_lifetime()
     }
+
+    #[inline]
+    pub fn get_priority(&self) -> u32 {
+        self.process.get_priority()
+    }
+
+    #[inline]
+    pub fn get_io_time(&self) -> Time {
+        self.process.get_io_time()
+    }
+
+    #[inline]
+    pub fn get_group(&self) -> u32 {
+        self.process.get_group()
+    }
+
+    // Processor time the process received, None if bookkeeping of the process is inconsistent
+    #[inline]
+    pub fn get_executed_time(&self) -> Option<Time> {
+        self.get_initial_time().checked_sub(self.process.get_total_time_left())
+    }
+
+    // Time from arrival to the first moment on processor, None if process never got it
+    #[inline]
+    pub fn get_response_time(&self) -> Option<Time> {
+        self.process.first_run_time.and_then(|first_run_time| first_run_time.checked_sub(self.get_creation_time()))
+    }
+
+    #[inline]
+    pub fn get_turnaround_time(&self) -> Option<Time> {
+        self.get_completion_time()?.checked_sub(self.get_creation_time())
+    }
+
+    // Time spent in ready queue, without time of processor and I/O bursts, None if bookkeeping of the process is inconsistent
+    #[inline]
+    pub fn get_waiting_time(&self) -> Option<Time> {
+        let executed_time = self.get_executed_time()?;
+        self.get_turnaround_time()?.checked_sub(executed_time)?.checked_sub(self.process.get_io_time())
+    }
 }
 
 impl Process {
-    pub fn new(id: u32, creation_time: u32, time: u32, lifetime: Option<u32>) -> Self {
-        Self { id, initial_time: time, time_left: time, creation_time, completion_time: None, call_count: 0, partial_waiting_time: 0f64, last_time_with_access: creation_time, lifetime, finished_state: None }
+    pub fn new(id: u32, creation_time: Time, description: &ProcessDescription) -> Self {
+        Self { id, initial_time: description.get_total_duration(), time_left: description.get_duration(), creation_time, completion_time: None, call_count: 0, partial_waiting_time: 0f64, last_time_with_access: creation_time, lifetime: description.get_lifetime(), priority: description.get_priority(), finished_state: None, burst_time: description.get_duration(), io_bursts: description.get_io_bursts().iter().copied().collect(), pending_io: None, blocked_since: 0, io_time: 0, tickets: description.get_tickets(), first_run_time: None, period: description.get_period(), group: description.get_group() }
     }
 
-    // Returns utilized time
-    pub fn work_for(&mut self, current_time: u32, time: u32, is_new_call: bool) -> u32 {
-        if let Some(lifetime) = self.lifetime {
-            if self.creation_time + lifetime < current_time + time {
-                let ans = match self.creation_time + lifetime > current_time {
-                    true => self.work_for(current_time, self.creation_time + lifetime - current_time, is_new_call),
+    // Returns utilized time, call is counted only when process does some work, so schedulers start the call only when utilized time isn't 0
+    pub fn work_for(&mut self, current_time: Time, time: Time, is_new_call: bool) -> Time {
+        if let Some(deadline) = self.get_deadline() {
+            if deadline < current_time.saturating_add(time) {
+                let ans = match deadline > current_time {
+                    true => self.work_for(current_time, deadline - current_time, is_new_call),
                     false => 0,
                 };
-                self.completion_time = Some(current_time + ans);
+                // Burst could end before the lifetime, then process completes or starts I/O as usual
+                if self.finished_state.is_some() || self.pending_io.is_some() {
+                    return ans;
+                }
+                self.completion_time = Some(current_time.saturating_add(ans));
                 self.finished_state = Some(false);
                 return ans;
             }
@@ -100,20 +275,30 @@ impl Process {
         if time == 0 {
             return time;
         }
+        if self.first_run_time.is_none() {
+            self.first_run_time = Some(current_time);
+        }
         if is_new_call {
             self.partial_waiting_time += (current_time - self.last_time_with_access) as f64 * (self.time_left / self.initial_time) as f64;
             self.call_count += 1;
         }
         if time >= self.time_left {
             let time_left = self.time_left;
-            self.completion_time = Some(current_time + self.time_left);
-            self.last_time_with_access = current_time + time_left;
+            self.last_time_with_access = current_time.saturating_add(time_left);
+            if let Some((io_time, duration)) = self.io_bursts.pop_front() {
+                self.time_left = duration;
+                self.burst_time = duration;
+                self.pending_io = Some(io_time);
+                self.blocked_since = current_time.saturating_add(time_left);
+                return time_left;
+            }
+            self.completion_time = Some(current_time.saturating_add(self.time_left));
             self.time_left = 0;
             self.finished_state = Some(true);
             return time_left;
         }
         self.time_left -= time;
-        self.last_time_with_access = current_time + time;
+        self.last_time_with_access = current_time.saturating_add(time);
         time
     }
 
@@ -123,22 +308,22 @@ impl Process {
     }
 
     #[inline]
-    pub fn get_initial_time(&self) -> u32 {
+    pub fn get_initial_time(&self) -> Time {
         self.initial_time
     }
 
     #[inline]
-    pub fn get_time_left(&self) -> u32 {
+    pub fn get_time_left(&self) -> Time {
         self.time_left
     }
 
     #[inline]
-    pub fn get_creation_time(&self) -> u32 {
+    pub fn get_creation_time(&self) -> Time {
         self.creation_time
     }
 
     #[inline]
-    pub fn get_completion_time(&self) -> Option<u32> {
+    pub fn get_completion_time(&self) -> Option<Time> {
         self.completion_time
     }
 
@@ -173,20 +358,123 @@ impl Process {
     }
 
     #[inline]
-    pub fn get_lifetime(&self) -> Option<u32> {
+    pub fn get_lifetime(&self) -> Option<Time> {
         self.lifetime
     }
+
+    #[inline]
+    pub fn get_priority(&self) -> u32 {
+        self.priority
+    }
+
+    #[inline]
+    pub fn get_tickets(&self) -> u32 {
+        self.tickets
+    }
+
+    #[inline]
+    pub fn get_period(&self) -> Option<Time> {
+        self.period
+    }
+
+    #[inline]
+    pub fn get_group(&self) -> u32 {
+        self.group
+    }
+
+    // Absolute time till which process has to end
+    #[inline]
+    pub fn get_deadline(&self) -> Option<Time> {
+        self.lifetime.map(|lifetime| self.creation_time.saturating_add(lifetime))
+    }
+
+    // Process with deadline which can't be met even if it gets processor right now
+    #[inline]
+    pub fn is_hopeless(&self, current_time: Time) -> bool {
+        self.get_deadline().is_some_and(|deadline| current_time.saturating_add(self.get_total_time_left()) > deadline)
+    }
+
+    // Scheduler gives up on the process, it ends unsuccessfully without using its remaining time
+    #[inline]
+    pub fn abandon(&mut self, current_time: Time) {
+        self.completion_time = Some(current_time);
+        self.finished_state = Some(false);
+    }
+
+    // Processor time left in every remaining burst, time_left covers only the current one
+    #[inline]
+    pub fn get_total_time_left(&self) -> Time {
+        self.io_bursts.iter().fold(self.time_left, |total, (_, duration)| total.saturating_add(*duration))
+    }
+
+    #[inline]
+    pub fn is_at_burst_start(&self) -> bool {
+        self.time_left == self.burst_time
+    }
+
+    // Process which has just ended its processor burst and waits for I/O
+    #[inline]
+    pub fn is_blocked(&self) -> bool {
+        self.pending_io.is_some()
+    }
+
+    // Returns duration of I/O burst the process is waiting for
+    #[inline]
+    pub fn start_io(&mut self) -> Time {
+        self.pending_io.take().unwrap()
+    }
+
+    #[inline]
+    pub fn end_io(&mut self, current_time: Time) {
+        self.io_time = self.io_time.saturating_add(current_time.saturating_sub(self.blocked_since));
+        self.last_time_with_access = current_time;
+    }
+
+    #[inline]
+    pub fn get_io_time(&self) -> Time {
+        self.io_time
+    }
+
+    // Time spent in ready queue since the process was running or returned from I/O
+    #[inline]
+    pub fn get_time_in_queue(&self, current_time: Time) -> Time {
+        current_time - self.last_time_with_access
+    }
+
+    // (waiting time + service time) / service time, where service time is length of the remaining part of the current burst
+    #[inline]
+    pub fn get_response_ratio(&self, current_time: Time) -> f64 {
+        (self.get_time_in_queue(current_time) + self.time_left) as f64 / self.time_left.max(1) as f64
+    }
+
+    // Priority improves by one for every full aging period spent in ready queue
+    #[inline]
+    pub fn get_effective_priority(&self, current_time: Time, aging_period: Option<Time>) -> u32 {
+        match aging_period {
+            Some(aging_period) => (self.priority as Time).saturating_sub(self.get_time_in_queue(current_time) / aging_period) as u32,
+            None => self.priority,
+        }
+    }
+
+    // Time after which effective priority will change if process keeps waiting
+    #[inline]
+    pub fn get_time_to_next_aging(&self, current_time: Time, aging_period: Option<Time>) -> Option<Time> {
+        match aging_period {
+            Some(aging_period) if self.get_effective_priority(current_time, Some(aging_period)) != 0 => Some(aging_period - self.get_time_in_queue(current_time) % aging_period),
+            _ => None,
+        }
+    }
 }
 
 impl PartialEq for Process {
     fn eq(&self, other: &Self) -> bool {
-        self.get_time_left() == other.get_time_left()
+        self.cmp(other).is_eq()
     }
 }
 
 impl PartialOrd for Process {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.get_time_left().partial_cmp(&other.get_time_left())
+        Some(self.cmp(other))
     }
 }
 
@@ -194,23 +482,227 @@ impl Eq for Process {
     
 }
 
+// Ties are broken by id, so the same process is taken again when its context switch didn't end within the last call
 impl Ord for Process {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.get_time_left().cmp(&other.get_time_left())
+        self.get_time_left().cmp(&other.get_time_left()).then(self.get_id().cmp(&other.get_id()))
+    }
+}
+
+// Charges configured cost every time processor starts working on a different process
+#[derive(Debug, Clone)]
+pub struct ContextSwitch {
+    cost: Time,
+    last_process_id: Option<u32>,
+    time_left: Time,
+    count: usize,
+    total_time: Time,
+}
+
+impl ContextSwitch {
+    #[inline]
+    pub fn new(cost: Time) -> Self {
+        Self { cost, last_process_id: None, time_left: 0, count: 0, total_time: 0 }
+    }
+
+    // Returns time spent on switching, it can be less than the cost if switch has not ended within n ticks.
+    // Schedulers give it at most the time till the next I/O completion and choose again if it hasn't ended, like when simulation is run tick by tick
+    pub fn switch_to(&mut self, process_id: u32, n: Time) -> Time {
+        if self.last_process_id != Some(process_id) {
+            self.last_process_id = Some(process_id);
+            self.time_left = self.cost;
+            self.count += 1;
+        }
+        let spent_time = self.time_left.min(n);
+        self.time_left -= spent_time;
+        self.total_time += spent_time;
+        spent_time
+    }
+
+    // Switch which has ended exactly with the simulated ticks is done, process is dispatched in the same call as if the call was longer
+    #[inline]
+    pub fn is_switching(&self) -> bool {
+        self.time_left != 0
+    }
+
+    #[inline]
+    pub fn get_count(&self) -> usize {
+        self.count
+    }
+
+    #[inline]
+    pub fn get_total_time(&self) -> Time {
+        self.total_time
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ShareEntry {
+    weight: u64,
+    joined_at_service: f64,
+    joined_at_time: Time,
+    ideal_time: f64,
+    received_time: Time,
+    ready_time: Time,
+}
+
+// Shares are parts of processor time which passed while process was ready
+#[derive(Debug, Clone)]
+pub struct ProcessShare {
+    process_id: u32,
+    weight: u64,
+    requested_share: f64,
+    achieved_share: f64,
+}
+
+impl ProcessShare {
+    #[inline]
+    pub fn get_process_id(&self) -> u32 {
+        self.process_id
+    }
+
+    #[inline]
+    pub fn get_weight(&self) -> u64 {
+        self.weight
+    }
+
+    #[inline]
+    pub fn get_requested_share(&self) -> f64 {
+        self.requested_share
+    }
+
+    #[inline]
+    pub fn get_achieved_share(&self) -> f64 {
+        self.achieved_share
+    }
+}
+
+// Compares processor time received by processes with ideal sharing, where at every moment each ready process gets part of processor equal to its weight divided by weight of every ready process
+#[derive(Debug, Clone, Default)]
+pub struct FairnessTracker {
+    service: f64, // Processor time which ideal sharing has given so far to a ready process of weight 1
+    processor_time: Time,
+    total_weight: u64,
+    processes: std::collections::HashMap<u32, ShareEntry>,
+    total_deviation: f64,
+    total_ideal_time: f64,
+    shares: Vec<ProcessShare>,
+}
+
+impl FairnessTracker {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Process arrives or returns from I/O
+    pub fn make_ready(&mut self, process_id: u32, weight: u64) {
+        let (service, processor_time) = (self.service, self.processor_time);
+        let entry = self.processes.entry(process_id).or_insert(ShareEntry { weight, joined_at_service: service, joined_at_time: processor_time, ideal_time: 0f64, received_time: 0, ready_time: 0 });
+        entry.joined_at_service = service;
+        entry.joined_at_time = processor_time;
+        self.total_weight += entry.weight;
+    }
+
+    pub fn run(&mut self, process_id: u32, worked_time: Time) {
+        if self.total_weight != 0 {
+            self.service += worked_time as f64 / self.total_weight as f64;
+        }
+        self.processor_time += worked_time;
+        if let Some(entry) = self.processes.get_mut(&process_id) {
+            entry.received_time += worked_time;
+        }
+    }
+
+    // Process blocks on I/O, it's not entitled to processor time until it's ready again
+    pub fn leave(&mut self, process_id: u32) {
+        if let Some(entry) = self.processes.get_mut(&process_id) {
+            entry.ideal_time += entry.weight as f64 * (self.service - entry.joined_at_service);
+            entry.ready_time += self.processor_time - entry.joined_at_time;
+            self.total_weight -= entry.weight;
+        }
+    }
+
+    pub fn finish(&mut self, process_id: u32) {
+        self.leave(process_id);
+        if let Some(entry) = self.processes.remove(&process_id) {
+            self.total_deviation += (entry.received_time as f64 - entry.ideal_time).abs();
+            self.total_ideal_time += entry.ideal_time;
+            let ready_time = entry.ready_time.max(1) as f64;
+            self.shares.push(ProcessShare { process_id, weight: entry.weight, requested_share: entry.ideal_time / ready_time, achieved_share: entry.received_time as f64 / ready_time });
+        }
+    }
+
+    // Sum of differences between received and ideal processor time of finished processes, relative to their ideal time
+    #[inline]
+    pub fn get_deviation(&self) -> f64 {
+        self.total_deviation / self.total_ideal_time.max(1f64)
+    }
+
+    #[inline]
+    pub fn get_shares(&self) -> &[ProcessShare] {
+        &self.shares
     }
 }
 
 pub trait CpuAccessManager {
-    fn add_process(&mut self, time: u32, lifetime: Option<u32>) -> u32;
+    fn add_process(&mut self, description: &ProcessDescription) -> Result<u32, SimulationError>;
     fn is_working(&self) -> bool;
     fn simulate_one_tick(&mut self) {
         self.simulate_n_ticks(1)
     }
-    fn simulate_n_ticks(&mut self, n: u32);
+    fn simulate_n_ticks(&mut self, n: Time);
+    // Runs until no process is left, clock stops at the end of the last process even if some processes expired or were abandoned before using their time
     fn simulate_till_end_of_every_process(&mut self);
-    fn get_total_working_time(&self) -> u32;
-    fn get_total_waiting_time(&self) -> u32;
-    fn get_working_time(&self) -> u32;
-    fn get_waiting_time(&self) -> u32;
-    fn finalize(self) -> Vec<ProcessStatistics>;
+    fn get_total_working_time(&self) -> Time;
+    fn get_total_waiting_time(&self) -> Time;
+    fn get_working_time(&self) -> Time;
+    fn get_waiting_time(&self) -> Time;
+    fn get_context_switches_count(&self) -> usize;
+    fn get_context_switches_time(&self) -> Time;
+    fn get_cores_utilization(&self) -> Vec<f64> {
+        let busy_time = self.get_total_working_time() + self.get_context_switches_time();
+        vec![self.get_total_working_time() as f64 / (busy_time + self.get_total_waiting_time()).max(1) as f64]
+    }
+    fn get_migrations_count(&self) -> usize {
+        0
+    }
+    // Deviation of received processor share from ideal one, only for schedulers which aim at fair sharing
+    fn get_fairness_deviation(&self) -> Option<f64> {
+        None
+    }
+    // Requested and achieved processor share of every finished process, only for schedulers which aim at fair sharing
+    fn get_processes_shares(&self) -> Vec<ProcessShare> {
+        Vec::new()
+    }
+    // Processes given up by scheduler because they couldn't meet their deadlines, only for deadline driven schedulers
+    fn get_abandoned_count(&self) -> Option<usize> {
+        None
+    }
+    // Average error of burst length estimates, only for schedulers which predict bursts
+    fn get_prediction_error(&self) -> Option<f64> {
+        None
+    }
+    fn get_io_utilization(&self) -> f64;
+    fn enable_trace(&mut self);
+    fn get_trace(&self) -> &crate::trace::Trace;
+    fn finalize(self: Box<Self>) -> Vec<ProcessStatistics>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Horizon checked before simulation doesn't include context switches, so process can run past the end of time range, its times saturate then
+    #[test]
+    fn process_at_end_of_time_range_saturates() {
+        let mut process = Process::new(0, Time::MAX - 3, &ProcessDescription::new(5, None, 0).with_io_bursts(vec![(1, 2)]));
+        assert_eq!(process.work_for(Time::MAX - 3, 5, true), 5);
+        assert!(process.is_blocked());
+        let io_time = process.start_io();
+        process.end_io(Time::MAX.saturating_add(io_time));
+        assert_eq!(process.work_for(Time::MAX, 2, true), 2);
+        assert_eq!(process.get_completion_time(), Some(Time::MAX));
+        assert_eq!(process.get_total_time_left(), 0);
+    }
 }
@@ -0,0 +1,427 @@
+use crate::cpu_access_manager::{self, CpuAccessManager, Process, ProcessDescription, SimulationError, Time};
+use crate::io_device::IoDevice;
+use crate::trace::Trace;
+
+// Processes without lifetime have no deadline, they run only when no process with deadline is ready
+#[inline]
+fn get_deadline_key(process: &Process) -> (bool, Time, u32) {
+    (process.get_deadline().is_none(), process.get_deadline().unwrap_or(0), process.get_id())
+}
+
+// Time which process may still wait and meet its deadline, it's None for processes without deadline
+#[inline]
+fn get_laxity(process: &Process, current_time: Time) -> Option<i128> {
+    process.get_deadline().map(|deadline| deadline as i128 - current_time as i128 - process.get_total_time_left() as i128)
+}
+
+// Index of the process with the smallest laxity, older processes win ties
+fn select_least_laxity(queue: &[Process], current_time: Time) -> Option<usize> {
+    queue.iter().enumerate().min_by_key(|(_, process)| (get_laxity(process, current_time).unwrap_or(i128::MAX), process.get_id())).map(|(index, _)| index)
+}
+
+// Gives up every waiting process which can't meet its deadline anymore, returns number of abandoned processes
+fn abandon_hopeless(queue: &mut Vec<Process>, current_time: Time, trace: &mut Trace, statistics: &mut Vec<cpu_access_manager::ProcessStatistics>, total_required_time: &mut Time) -> usize {
+    let mut ans = 0;
+    let mut index = 0;
+    while index < queue.len() {
+        if queue[index].is_hopeless(current_time) {
+            let mut process = queue.swap_remove(index);
+            process.abandon(current_time);
+            trace.abandon(current_time, &process);
+            *total_required_time -= process.get_total_time_left();
+            statistics.push(process.finalize());
+            ans += 1;
+        }
+        else {
+            index += 1;
+        }
+    }
+    ans
+}
+
+// Earliest deadline first, process with the closest absolute deadline runs and it's checked again whenever something is added to the queue
+#[derive(Debug, Clone)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct EDF {
+    queue: Vec<Process>,
+    abandon_hopeless: bool,
+    abandoned_count: usize,
+    is_ending: bool,
+    working_time: Time,
+    waiting_time: Time,
+    total_working_time: Time,
+    total_waiting_time: Time,
+    next_id: u32,
+    current_time: Time,
+    statistics: Vec<cpu_access_manager::ProcessStatistics>,
+    total_required_time: Time,
+    current_id: Option<u32>,
+    context_switch: cpu_access_manager::ContextSwitch,
+    trace: Trace,
+    io_device: IoDevice<Process>,
+}
+
+impl EDF {
+    #[inline]
+    pub fn new(abandon_hopeless: bool, context_switch_cost: Time) -> Self {
+        Self { queue: Vec::new(), abandon_hopeless, abandoned_count: 0, is_ending: false, working_time: 0, waiting_time: 0, total_working_time: 0, total_waiting_time: 0, next_id: 0, current_time: 0, statistics: Vec::new(), total_required_time: 0, current_id: None, context_switch: cpu_access_manager::ContextSwitch::new(context_switch_cost), trace: Trace::new(), io_device: IoDevice::new() }
+    }
+
+    fn return_from_io(&mut self) {
+        for (mut process, time) in self.io_device.advance_to(self.current_time) {
+            process.end_io(time);
+            self.trace.io_completion(time, process.get_id());
+            self.queue.push(process);
+        }
+    }
+}
+
+impl CpuAccessManager for EDF {
+    fn add_process(&mut self, description: &ProcessDescription) -> Result<u32, SimulationError> {
+        description.validate()?;
+        let total_required_time = self.total_required_time.checked_add(description.get_total_duration()).ok_or(SimulationError::TimeOverflow)?;
+        self.queue.push(Process::new(self.next_id, self.current_time, description));
+        let ans = self.next_id;
+        self.trace.arrival(self.current_time, ans);
+        self.next_id += 1;
+        self.total_required_time = total_required_time;
+        Ok(ans)
+    }
+
+    #[inline]
+    fn is_working(&self) -> bool {
+        !self.queue.is_empty() || self.io_device.is_working()
+    }
+
+    #[inline]
+    fn simulate_one_tick(&mut self) {
+        self.simulate_n_ticks(1)
+    }
+
+    fn simulate_n_ticks(&mut self, mut n: Time) {
+        while n != 0 {
+            self.return_from_io();
+            if self.abandon_hopeless {
+                self.abandoned_count += abandon_hopeless(&mut self.queue, self.current_time, &mut self.trace, &mut self.statistics, &mut self.total_required_time);
+            }
+            if self.is_ending {
+                n = n.min(self.total_required_time);
+            }
+            if let Some(index) = self.queue.iter().enumerate().min_by_key(|(_, process)| get_deadline_key(process)).map(|(index, _)| index) {
+                let mut current = self.queue.swap_remove(index);
+                let switch_time = self.context_switch.switch_to(current.get_id(), self.io_device.limit(self.current_time, n));
+                n -= switch_time;
+                self.current_time += switch_time;
+                if self.context_switch.is_switching() {
+                    self.queue.push(current);
+                    continue;
+                }
+                let is_new_call = self.current_id != Some(current.get_id());
+                let worked_time = current.work_for(self.current_time, self.io_device.limit(self.current_time, n), is_new_call);
+                if worked_time != 0 {
+                    self.current_id = Some(current.get_id());
+                }
+                n -= worked_time;
+                self.trace.run(current.get_id(), self.current_time, worked_time);
+                self.current_time += worked_time;
+                self.total_required_time -= worked_time;
+                self.total_working_time += worked_time;
+                if current.is_finished() {
+                    self.trace.finish(self.current_time, &current);
+                    self.total_required_time -= current.get_total_time_left();
+                    self.statistics.push(current.finalize());
+                }
+                else if current.is_blocked() {
+                    let io_time = current.start_io();
+                    self.trace.block(self.current_time, &current);
+                    self.io_device.block(self.current_time, current, io_time);
+                    self.current_id = None;
+                }
+                else {
+                    self.queue.push(current);
+                }
+            }
+            else {
+                let idle_time = self.io_device.limit(self.current_time, n);
+                self.waiting_time = idle_time;
+                self.working_time = 0;
+                self.total_waiting_time += self.waiting_time;
+                self.current_time += idle_time;
+                n -= idle_time;
+            }
+        }
+    }
+
+    #[inline]
+    fn get_total_working_time(&self) -> Time {
+        self.total_working_time
+    }
+
+    #[inline]
+    fn get_total_waiting_time(&self) -> Time {
+        self.total_waiting_time
+    }
+
+    #[inline]
+    fn get_working_time(&self) -> Time {
+        self.working_time
+    }
+
+    #[inline]
+    fn get_waiting_time(&self) -> Time {
+        self.waiting_time
+    }
+
+    #[inline]
+    fn get_context_switches_count(&self) -> usize {
+        self.context_switch.get_count()
+    }
+
+    #[inline]
+    fn get_context_switches_time(&self) -> Time {
+        self.context_switch.get_total_time()
+    }
+
+    #[inline]
+    fn get_io_utilization(&self) -> f64 {
+        self.io_device.get_working_time() as f64 / self.current_time.max(1) as f64
+    }
+
+    #[inline]
+    fn get_abandoned_count(&self) -> Option<usize> {
+        Some(self.abandoned_count)
+    }
+
+    #[inline]
+    fn enable_trace(&mut self) {
+        self.trace.enable()
+    }
+
+    #[inline]
+    fn get_trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    #[inline]
+    fn simulate_till_end_of_every_process(&mut self) {
+        self.is_ending = true;
+        while self.is_working() {
+            self.simulate_n_ticks(self.total_required_time)
+        }
+        self.is_ending = false;
+    }
+
+    #[inline]
+    fn finalize(mut self: Box<Self>) -> Vec<cpu_access_manager::ProcessStatistics> {
+        self.simulate_till_end_of_every_process();
+        self.statistics
+    }
+}
+
+// Least laxity first, laxity of the running process doesn't change while laxity of waiting ones decreases, so they are compared again as soon as any of them may overtake it
+#[derive(Debug, Clone)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct LLF {
+    quantum_time: Time,
+    queue: Vec<Process>,
+    abandon_hopeless: bool,
+    abandoned_count: usize,
+    is_ending: bool,
+    working_time: Time,
+    waiting_time: Time,
+    total_working_time: Time,
+    total_waiting_time: Time,
+    next_id: u32,
+    current_time: Time,
+    statistics: Vec<cpu_access_manager::ProcessStatistics>,
+    total_required_time: Time,
+    current_id: Option<u32>,
+    is_new_call: bool,
+    time_left_for_current_process: Time, // Until some waiting process overtakes it, 0 - choose again
+    context_switch: cpu_access_manager::ContextSwitch,
+    trace: Trace,
+    io_device: IoDevice<Process>,
+}
+
+impl LLF {
+    // Processes with equal laxity would switch every tick, so in such case the running one keeps processor for a quantum
+    #[inline]
+    pub fn new(quantum_time: Time, abandon_hopeless: bool, context_switch_cost: Time) -> Result<Self, SimulationError> {
+        if quantum_time == 0 {
+            return Err(SimulationError::InvalidParameter("Quant cannot be 0"));
+        }
+        Ok(Self { quantum_time, queue: Vec::new(), abandon_hopeless, abandoned_count: 0, is_ending: false, working_time: 0, waiting_time: 0, total_working_time: 0, total_waiting_time: 0, next_id: 0, current_time: 0, statistics: Vec::new(), total_required_time: 0, current_id: None, is_new_call: false, time_left_for_current_process: 0, context_switch: cpu_access_manager::ContextSwitch::new(context_switch_cost), trace: Trace::new(), io_device: IoDevice::new() })
+    }
+
+    fn return_from_io(&mut self) {
+        for (mut process, time) in self.io_device.advance_to(self.current_time) {
+            process.end_io(time);
+            self.trace.io_completion(time, process.get_id());
+            self.queue.push(process);
+            self.time_left_for_current_process = 0;
+        }
+    }
+
+    // Time after which some waiting process will have smaller laxity than the current one
+    fn get_time_to_overtake(&self, current: &Process) -> Option<Time> {
+        let laxity = get_laxity(current, self.current_time)?;
+        self.queue.iter().filter_map(|process| get_laxity(process, self.current_time)).map(|waiting_laxity| match waiting_laxity - laxity {
+            difference if difference <= 0 => self.quantum_time,
+            difference => difference.min(Time::MAX as i128) as Time,
+        }).min()
+    }
+}
+
+impl CpuAccessManager for LLF {
+    fn add_process(&mut self, description: &ProcessDescription) -> Result<u32, SimulationError> {
+        description.validate()?;
+        let total_required_time = self.total_required_time.checked_add(description.get_total_duration()).ok_or(SimulationError::TimeOverflow)?;
+        self.queue.push(Process::new(self.next_id, self.current_time, description));
+        self.time_left_for_current_process = 0;
+        let ans = self.next_id;
+        self.trace.arrival(self.current_time, ans);
+        self.next_id += 1;
+        self.total_required_time = total_required_time;
+        Ok(ans)
+    }
+
+    #[inline]
+    fn is_working(&self) -> bool {
+        !self.queue.is_empty() || self.io_device.is_working()
+    }
+
+    #[inline]
+    fn simulate_one_tick(&mut self) {
+        self.simulate_n_ticks(1)
+    }
+
+    fn simulate_n_ticks(&mut self, mut n: Time) {
+        while n != 0 {
+            self.return_from_io();
+            if self.abandon_hopeless {
+                self.abandoned_count += abandon_hopeless(&mut self.queue, self.current_time, &mut self.trace, &mut self.statistics, &mut self.total_required_time);
+            }
+            if self.is_ending {
+                n = n.min(self.total_required_time);
+            }
+            // Running process keeps processor till it's overtaken, even if simulation is split into many calls
+            let current_index = self.current_id.filter(|_| self.time_left_for_current_process != 0).and_then(|id| self.queue.iter().position(|process| process.get_id() == id));
+            if let Some(index) = current_index.or_else(|| select_least_laxity(&self.queue, self.current_time)) {
+                let mut current = self.queue.swap_remove(index);
+                let switch_time = self.context_switch.switch_to(current.get_id(), self.io_device.limit(self.current_time, n));
+                n -= switch_time;
+                self.current_time += switch_time;
+                if self.context_switch.is_switching() {
+                    self.queue.push(current);
+                    self.time_left_for_current_process = 0;
+                    continue;
+                }
+                if current_index.is_none() {
+                    self.time_left_for_current_process = self.get_time_to_overtake(&current).unwrap_or(Time::MAX);
+                }
+                let time_for_process = self.time_left_for_current_process.min(n);
+                if self.current_id != Some(current.get_id()) {
+                    self.current_id = Some(current.get_id());
+                    self.is_new_call = true;
+                }
+                let worked_time = current.work_for(self.current_time, self.io_device.limit(self.current_time, time_for_process), self.is_new_call);
+                if worked_time != 0 {
+                    self.is_new_call = false;
+                }
+                n -= worked_time;
+                self.time_left_for_current_process -= worked_time;
+                self.trace.run(current.get_id(), self.current_time, worked_time);
+                self.current_time += worked_time;
+                self.total_required_time -= worked_time;
+                self.total_working_time += worked_time;
+                if current.is_finished() {
+                    self.trace.finish(self.current_time, &current);
+                    self.total_required_time -= current.get_total_time_left();
+                    self.statistics.push(current.finalize());
+                }
+                else if current.is_blocked() {
+                    let io_time = current.start_io();
+                    self.trace.block(self.current_time, &current);
+                    self.io_device.block(self.current_time, current, io_time);
+                    self.current_id = None;
+                }
+                else {
+                    self.queue.push(current);
+                }
+            }
+            else {
+                let idle_time = self.io_device.limit(self.current_time, n);
+                self.waiting_time = idle_time;
+                self.working_time = 0;
+                self.total_waiting_time += self.waiting_time;
+                self.current_time += idle_time;
+                n -= idle_time;
+            }
+        }
+    }
+
+    #[inline]
+    fn get_total_working_time(&self) -> Time {
+        self.total_working_time
+    }
+
+    #[inline]
+    fn get_total_waiting_time(&self) -> Time {
+        self.total_waiting_time
+    }
+
+    #[inline]
+    fn get_working_time(&self) -> Time {
+        self.working_time
+    }
+
+    #[inline]
+    fn get_waiting_time(&self) -> Time {
+        self.waiting_time
+    }
+
+    #[inline]
+    fn get_context_switches_count(&self) -> usize {
+        self.context_switch.get_count()
+    }
+
+    #[inline]
+    fn get_context_switches_time(&self) -> Time {
+        self.context_switch.get_total_time()
+    }
+
+    #[inline]
+    fn get_io_utilization(&self) -> f64 {
+        self.io_device.get_working_time() as f64 / self.current_time.max(1) as f64
+    }
+
+    #[inline]
+    fn get_abandoned_count(&self) -> Option<usize> {
+        Some(self.abandoned_count)
+    }
+
+    #[inline]
+    fn enable_trace(&mut self) {
+        self.trace.enable()
+    }
+
+    #[inline]
+    fn get_trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    #[inline]
+    fn simulate_till_end_of_every_process(&mut self) {
+        self.is_ending = true;
+        while self.is_working() {
+            self.simulate_n_ticks(self.total_required_time)
+        }
+        self.is_ending = false;
+    }
+
+    #[inline]
+    fn finalize(mut self: Box<Self>) -> Vec<cpu_access_manager::ProcessStatistics> {
+        self.simulate_till_end_of_every_process();
+        self.statistics
+    }
+}
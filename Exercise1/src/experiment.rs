@@ -0,0 +1,216 @@
+use crate::cpu_access_manager::SimulationError;
+use crate::generator::{self, GeneratorSettings};
+use crate::report::{self, TableFormat};
+use crate::simulator::{self, SimulationSettings};
+
+// Two-sided 95% critical values of Student's t-distribution for 1..=30 degrees of freedom
+const T_CRITICAL_VALUES: [f64; 30] = [12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160, 2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056, 2.052, 2.048, 2.045, 2.042];
+
+// Above 30 every range uses the value of its smallest degrees of freedom, so intervals are never narrower than 95%
+fn get_t_critical_value(degrees_of_freedom: usize) -> f64 {
+    match degrees_of_freedom {
+        0 => f64::INFINITY,
+        1..=30 => T_CRITICAL_VALUES[degrees_of_freedom - 1],
+        31..=60 => 2.040,
+        61..=120 => 2.000,
+        _ => 1.980,
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MetricSummary {
+    runs_count: usize,
+    mean: f64,
+    standard_deviation: f64,
+    confidence_interval: (f64, f64),
+}
+
+impl MetricSummary {
+    // Sample standard deviation, interval is infinite when there is only one value
+    fn new(values: &[f64]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let runs_count = values.len();
+        let mean = values.iter().sum::<f64>() / runs_count as f64;
+        let standard_deviation = match runs_count {
+            1 => 0f64,
+            _ => (values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / (runs_count - 1) as f64).sqrt(),
+        };
+        let half_width = match runs_count {
+            1 => f64::INFINITY,
+            _ => get_t_critical_value(runs_count - 1) * standard_deviation / (runs_count as f64).sqrt(),
+        };
+        Some(Self { runs_count, mean, standard_deviation, confidence_interval: (mean - half_width, mean + half_width) })
+    }
+
+    #[inline]
+    pub fn get_runs_count(&self) -> usize {
+        self.runs_count
+    }
+
+    #[inline]
+    pub fn get_mean(&self) -> f64 {
+        self.mean
+    }
+
+    #[inline]
+    pub fn get_standard_deviation(&self) -> f64 {
+        self.standard_deviation
+    }
+
+    #[inline]
+    pub fn get_confidence_interval(&self) -> (f64, f64) {
+        self.confidence_interval
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExperimentResults {
+    base_seed: u64,
+    algorithms: Vec<String>,
+    metrics: Vec<&'static str>,
+    values: Vec<Vec<Vec<Option<f64>>>>, // Indexed by run, algorithm and metric
+}
+
+impl ExperimentResults {
+    #[inline]
+    pub fn get_base_seed(&self) -> u64 {
+        self.base_seed
+    }
+
+    #[inline]
+    pub fn get_runs_count(&self) -> usize {
+        self.values.len()
+    }
+
+    #[inline]
+    pub fn get_algorithms(&self) -> &[String] {
+        &self.algorithms
+    }
+
+    #[inline]
+    pub fn get_metrics(&self) -> &[&'static str] {
+        &self.metrics
+    }
+
+    // Runs in which metric doesn't apply to the algorithm are skipped
+    pub fn get_summary(&self, algorithm: usize, metric: usize) -> Option<MetricSummary> {
+        MetricSummary::new(&self.values.iter().filter_map(|run| run[algorithm][metric]).collect::<Vec<f64>>())
+    }
+
+    // Part of runs in which the first algorithm was strictly better than the second one, ties count for neither
+    pub fn get_win_ratio(&self, algorithm: usize, other: usize, metric: usize) -> Option<f64> {
+        let higher_is_better = report::is_higher_better(self.metrics[metric]);
+        let compared: Vec<bool> = self.values.iter().filter_map(|run| match (run[algorithm][metric], run[other][metric]) {
+            (Some(value), Some(other_value)) => Some(match higher_is_better {
+                true => value > other_value,
+                false => value < other_value,
+            }),
+            _ => None,
+        }).collect();
+        match compared.is_empty() {
+            true => None,
+            false => Some(compared.iter().filter(|won| **won).count() as f64 / compared.len() as f64),
+        }
+    }
+
+    // One row per algorithm and metric
+    pub fn to_summary_table(&self, format: TableFormat) -> String {
+        let header: Vec<String> = ["algorithm", "metric", "runs", "mean", "standard_deviation", "ci95_low", "ci95_high"].into_iter().map(str::to_owned).collect();
+        let mut rows = Vec::new();
+        for (algorithm, name) in self.algorithms.iter().enumerate() {
+            for (metric, metric_name) in self.metrics.iter().enumerate() {
+                let mut row = vec![name.clone(), metric_name.to_string()];
+                match self.get_summary(algorithm, metric) {
+                    Some(summary) => {
+                        let (low, high) = summary.get_confidence_interval();
+                        row.extend([summary.get_runs_count().to_string(), report::format_value(summary.get_mean()), report::format_value(summary.get_standard_deviation()), report::format_value(low), report::format_value(high)]);
+                    },
+                    None => row.extend(["0".to_owned(), String::new(), String::new(), String::new(), String::new()]),
+                }
+                rows.push(row);
+            }
+        }
+        report::render_table(&header, &rows, format)
+    }
+
+    // Cell in row A and column B is percent of runs in which A was better than B
+    pub fn to_pairwise_table(&self, metric: usize, format: TableFormat) -> String {
+        let header: Vec<String> = [format!("{} (row beats column, %)", self.metrics[metric])].into_iter().chain(self.algorithms.iter().cloned()).collect();
+        let rows: Vec<Vec<String>> = self.algorithms.iter().enumerate().map(|(algorithm, name)| [name.clone()].into_iter().chain((0..self.algorithms.len()).map(|other| match (algorithm == other, self.get_win_ratio(algorithm, other, metric)) {
+            (true, _) | (_, None) => String::new(),
+            (false, Some(ratio)) => format!("{:.1}", ratio * 100f64),
+        })).collect()).collect();
+        report::render_table(&header, &rows, format)
+    }
+}
+
+// Every run gets its own workload generated with seed equal to base seed plus number of the run, traces are not recorded
+pub fn run_experiment(generator_settings: &GeneratorSettings, settings: &SimulationSettings, runs_count: usize) -> Result<ExperimentResults, SimulationError> {
+    if runs_count == 0 {
+        return Err(SimulationError::InvalidParameter("Experiment needs at least one run"));
+    }
+    let base_seed = generator_settings.seed.unwrap_or_else(rand::random);
+    let mut algorithms = Vec::new();
+    let mut metrics = Vec::new();
+    let mut values = Vec::with_capacity(runs_count);
+    for run in 0..runs_count as u64 {
+        let processes = generator::generate(&GeneratorSettings { seed: Some(base_seed.wrapping_add(run)), ..generator_settings.clone() })?;
+        let results = simulator::simulate_every(processes, &SimulationSettings { trace_directory: None, lottery_seed: settings.lottery_seed.map(|seed| seed.wrapping_add(run)), ..settings.clone() })?;
+        if algorithms.is_empty() {
+            algorithms = results.iter().map(|(name, _)| name.clone()).collect();
+        }
+        values.push(results.iter().map(|(_, statistics)| {
+            let run_metrics = report::get_metrics(statistics);
+            if metrics.is_empty() {
+                metrics = run_metrics.iter().map(|(metric, _)| *metric).collect();
+            }
+            run_metrics.into_iter().map(|(_, value)| value).collect()
+        }).collect());
+    }
+    Ok(ExperimentResults { base_seed, algorithms, metrics, values })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confidence_interval_is_at_least_95_percent() {
+        // Exact critical values for 31, 61 and 121 degrees of freedom are 2.040, 2.000 and 1.980
+        assert_eq!(get_t_critical_value(30), 2.042);
+        assert_eq!(get_t_critical_value(31), 2.040);
+        assert_eq!(get_t_critical_value(60), 2.040);
+        assert_eq!(get_t_critical_value(61), 2.000);
+        assert_eq!(get_t_critical_value(121), 1.980);
+        let summary = MetricSummary::new(&[2f64, 4f64, 4f64, 4f64, 5f64, 5f64, 7f64, 9f64]).unwrap();
+        assert_eq!(summary.get_runs_count(), 8);
+        assert_eq!(summary.get_mean(), 5f64);
+        assert!((summary.get_standard_deviation() - (32f64 / 7f64).sqrt()).abs() < 1e-12);
+        let (low, high) = summary.get_confidence_interval();
+        // 2.365 for 7 degrees of freedom times standard deviation divided by square root of 8
+        assert!((low - 3.212228).abs() < 1e-6 && (high - 6.787772).abs() < 1e-6);
+        let single = MetricSummary::new(&[3f64]).unwrap();
+        assert_eq!((single.get_standard_deviation(), single.get_confidence_interval()), (0f64, (f64::NEG_INFINITY, f64::INFINITY)));
+        assert!(MetricSummary::new(&[]).is_none());
+    }
+
+    #[test]
+    fn win_ratio_ignores_ties_and_follows_metric_direction() {
+        let results = ExperimentResults { base_seed: 0, algorithms: vec!["A".to_owned(), "B".to_owned()], metrics: vec!["average_waiting_time", "cpu_utilization"], values: vec![
+            vec![vec![Some(1f64), Some(0.5)], vec![Some(2f64), Some(0.7)]],
+            vec![vec![Some(3f64), Some(0.9)], vec![Some(3f64), Some(0.9)]],
+            vec![vec![Some(5f64), Some(0.8)], vec![Some(4f64), Some(0.6)]],
+            vec![vec![Some(1f64), None], vec![Some(2f64), Some(0.1)]],
+        ] };
+        // Lower waiting time wins, tie in the second run counts for neither algorithm
+        assert_eq!(results.get_win_ratio(0, 1, 0), Some(0.5));
+        assert_eq!(results.get_win_ratio(1, 0, 0), Some(0.25));
+        // Higher utilization wins, run without value is skipped
+        assert_eq!(results.get_win_ratio(0, 1, 1), Some(1f64 / 3f64));
+        assert_eq!(results.get_win_ratio(1, 0, 1), Some(1f64 / 3f64));
+        let no_values = ExperimentResults { values: vec![vec![vec![Some(1f64), None], vec![Some(2f64), None]]], ..results };
+        assert_eq!(no_values.get_win_ratio(0, 1, 1), None);
+    }
+}
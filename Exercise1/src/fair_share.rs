@@ -0,0 +1,282 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::cpu_access_manager::{self, CpuAccessManager, Process, ProcessDescription, SimulationError, Time};
+use crate::io_device::IoDevice;
+use crate::proportional_share::STRIDE_ONE;
+use crate::trace::Trace;
+
+// Picks process of the group which got the processor, it's chosen again every quantum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::upper_case_acronyms)]
+pub enum GroupPolicy {
+    RoundRobin, // Process which used its quantum goes to the end of its group queue
+    FCFS, // The earliest arrival of the group
+    SJF, // The shortest remaining burst
+    Priority, // The most important one, ties are broken by arrival
+}
+
+impl GroupPolicy {
+    fn select(&self, queue: &VecDeque<Process>) -> Option<usize> {
+        let candidates = queue.iter().enumerate();
+        match self {
+            GroupPolicy::RoundRobin => (!queue.is_empty()).then_some(0),
+            GroupPolicy::FCFS => candidates.min_by_key(|(_, process)| process.get_id()).map(|(index, _)| index),
+            GroupPolicy::SJF => candidates.min_by_key(|(_, process)| (process.get_time_left(), process.get_id())).map(|(index, _)| index),
+            GroupPolicy::Priority => candidates.min_by_key(|(_, process)| (process.get_priority(), process.get_id())).map(|(index, _)| index),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Group {
+    share: u32,
+    pass: u64, // Grows with processor time received by the group, the slower the bigger share it has
+    queue: VecDeque<Process>,
+}
+
+// Two levels of scheduling, every quantum the ready group with the smallest pass (like in stride scheduling) gets the processor and its policy picks the process
+#[derive(Debug, Clone)]
+pub struct FairShare {
+    quantum_time: Time,
+    shares: Vec<u32>, // Share of every group by its number, groups without share get 1
+    policy: GroupPolicy,
+    groups: BTreeMap<u32, Group>,
+    current_process: Option<Process>,
+    time_left_for_current_process: Time,
+    min_pass: u64,
+    is_ending: bool,
+    working_time: Time,
+    waiting_time: Time,
+    total_working_time: Time,
+    total_waiting_time: Time,
+    next_id: u32,
+    current_time: Time,
+    statistics: Vec<cpu_access_manager::ProcessStatistics>,
+    total_required_time: Time,
+    context_switch: cpu_access_manager::ContextSwitch,
+    trace: Trace,
+    io_device: IoDevice<Process>,
+}
+
+impl FairShare {
+    pub fn new(quantum_time: Time, shares: Vec<u32>, policy: GroupPolicy, context_switch_cost: Time) -> Result<Self, SimulationError> {
+        if quantum_time == 0 {
+            return Err(SimulationError::InvalidParameter("Quant cannot be 0"));
+        }
+        if shares.contains(&0) {
+            return Err(SimulationError::InvalidParameter("Group share cannot be 0"));
+        }
+        if shares.iter().any(|share| *share as u64 > STRIDE_ONE) {
+            return Err(SimulationError::InvalidParameter("Group share cannot be greater than STRIDE_ONE"));
+        }
+        Ok(Self { quantum_time, shares, policy, groups: BTreeMap::new(), current_process: None, time_left_for_current_process: quantum_time, min_pass: 0, is_ending: false, working_time: 0, waiting_time: 0, total_working_time: 0, total_waiting_time: 0, next_id: 0, current_time: 0, statistics: Vec::new(), total_required_time: 0, context_switch: cpu_access_manager::ContextSwitch::new(context_switch_cost), trace: Trace::new(), io_device: IoDevice::new() })
+    }
+
+    // Group can't save up pass while none of its processes is ready, otherwise it would monopolize processor after return
+    fn make_ready(&mut self, process: Process) {
+        let is_running = self.current_process.as_ref().is_some_and(|current| current.get_group() == process.get_group());
+        let share = self.shares.get(process.get_group() as usize).copied().unwrap_or(1);
+        let group = self.groups.entry(process.get_group()).or_insert(Group { share, pass: self.min_pass, queue: VecDeque::new() });
+        if group.queue.is_empty() && !is_running {
+            group.pass = group.pass.max(self.min_pass);
+        }
+        group.queue.push_back(process);
+    }
+
+    fn return_from_io(&mut self) {
+        for (mut process, time) in self.io_device.advance_to(self.current_time) {
+            process.end_io(time);
+            self.trace.io_completion(time, process.get_id());
+            self.make_ready(process);
+        }
+    }
+
+    fn select(&mut self) -> Option<Process> {
+        let group = self.groups.values_mut().filter(|group| !group.queue.is_empty()).min_by_key(|group| group.pass)?;
+        let index = self.policy.select(&group.queue)?;
+        group.queue.remove(index)
+    }
+
+    // Group which has just run is included also when its process left the processor, so minimum doesn't depend on how simulation is split into calls
+    fn update_min_pass(&mut self, running_group: u32) {
+        let running = self.groups[&running_group].pass;
+        let ready = self.groups.values().filter(|group| !group.queue.is_empty()).map(|group| group.pass).min();
+        self.min_pass = self.min_pass.max(ready.map_or(running, |pass| pass.min(running)));
+    }
+}
+
+impl CpuAccessManager for FairShare {
+    fn add_process(&mut self, description: &ProcessDescription) -> Result<u32, SimulationError> {
+        description.validate()?;
+        let total_required_time = self.total_required_time.checked_add(description.get_total_duration()).ok_or(SimulationError::TimeOverflow)?;
+        self.make_ready(Process::new(self.next_id, self.current_time, description));
+        let ans = self.next_id;
+        self.trace.arrival(self.current_time, ans);
+        self.next_id += 1;
+        self.total_required_time = total_required_time;
+        Ok(ans)
+    }
+
+    #[inline]
+    fn is_working(&self) -> bool {
+        self.current_process.is_some() || self.groups.values().any(|group| !group.queue.is_empty()) || self.io_device.is_working()
+    }
+
+    #[inline]
+    fn simulate_one_tick(&mut self) {
+        self.simulate_n_ticks(1)
+    }
+
+    fn simulate_n_ticks(&mut self, mut n: Time) {
+        while n != 0 {
+            self.return_from_io();
+            if self.is_ending {
+                n = n.min(self.total_required_time);
+            }
+            if self.current_process.is_none() {
+                self.current_process = self.select();
+                self.time_left_for_current_process = self.quantum_time;
+            }
+            if let Some(mut current) = self.current_process.take() {
+                let switch_time = self.context_switch.switch_to(current.get_id(), self.io_device.limit(self.current_time, n));
+                n -= switch_time;
+                self.current_time += switch_time;
+                if self.context_switch.is_switching() {
+                    self.current_process = Some(current);
+                    continue;
+                }
+                let time_for_process = self.io_device.limit(self.current_time, n).min(self.time_left_for_current_process);
+                let worked_time = current.work_for(self.current_time, time_for_process, self.time_left_for_current_process == self.quantum_time);
+                n -= worked_time;
+                // Group which used only part of quantum is charged only for that part, the same however the quantum was split into calls
+                let group_id = current.get_group();
+                let group = self.groups.get_mut(&group_id).unwrap();
+                let charge = |used: Time| (STRIDE_ONE / group.share as u64).saturating_mul(used) / self.quantum_time;
+                let used_time = self.quantum_time - self.time_left_for_current_process;
+                group.pass += charge(used_time + worked_time) - charge(used_time);
+                self.time_left_for_current_process -= worked_time;
+                self.trace.run(current.get_id(), self.current_time, worked_time);
+                self.current_time += worked_time;
+                self.total_required_time -= worked_time;
+                self.total_working_time += worked_time;
+                if current.is_finished() {
+                    self.trace.finish(self.current_time, &current);
+                    self.total_required_time -= current.get_total_time_left();
+                    self.statistics.push(current.finalize());
+                }
+                else if current.is_blocked() {
+                    let io_time = current.start_io();
+                    self.trace.block(self.current_time, &current);
+                    self.io_device.block(self.current_time, current, io_time);
+                }
+                else if self.time_left_for_current_process == 0 {
+                    group.queue.push_back(current);
+                }
+                else {
+                    self.current_process = Some(current);
+                }
+                self.update_min_pass(group_id);
+            }
+            else {
+                let idle_time = self.io_device.limit(self.current_time, n);
+                self.waiting_time = idle_time;
+                self.working_time = 0;
+                self.total_waiting_time += self.waiting_time;
+                self.current_time += idle_time;
+                n -= idle_time;
+            }
+        }
+    }
+
+    #[inline]
+    fn get_total_working_time(&self) -> Time {
+        self.total_working_time
+    }
+
+    #[inline]
+    fn get_total_waiting_time(&self) -> Time {
+        self.total_waiting_time
+    }
+
+    #[inline]
+    fn get_working_time(&self) -> Time {
+        self.working_time
+    }
+
+    #[inline]
+    fn get_waiting_time(&self) -> Time {
+        self.waiting_time
+    }
+
+    #[inline]
+    fn get_context_switches_count(&self) -> usize {
+        self.context_switch.get_count()
+    }
+
+    #[inline]
+    fn get_context_switches_time(&self) -> Time {
+        self.context_switch.get_total_time()
+    }
+
+    #[inline]
+    fn get_io_utilization(&self) -> f64 {
+        self.io_device.get_working_time() as f64 / self.current_time.max(1) as f64
+    }
+
+    #[inline]
+    fn enable_trace(&mut self) {
+        self.trace.enable()
+    }
+
+    #[inline]
+    fn get_trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    #[inline]
+    fn simulate_till_end_of_every_process(&mut self) {
+        self.is_ending = true;
+        while self.is_working() {
+            self.simulate_n_ticks(self.total_required_time)
+        }
+        self.is_ending = false;
+    }
+
+    #[inline]
+    fn finalize(mut self: Box<Self>) -> Vec<cpu_access_manager::ProcessStatistics> {
+        self.simulate_till_end_of_every_process();
+        self.statistics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::invariant_tests::{check_invariants, Run};
+    use crate::rotating;
+    use crate::simulator::{self, Processes, SimulationSettings};
+
+    use super::*;
+
+    // Group with single long process competes with group of many short ones, it gets the processor in proportion to shares of both groups, not of processes
+    #[test]
+    fn fair_share_divides_processor_among_groups() {
+        let workload = Processes::from((0..10).map(|id| (0, ProcessDescription::new(match id {
+            0 => 900,
+            _ => 200,
+        }, None, 0).with_group((id == 0) as u32))).collect::<Vec<_>>());
+        let completion_time = |run: &Run| run.statistics.iter().find(|process| process.get_group() == 1).unwrap().get_completion_time().unwrap();
+        for (shares, expected) in [(vec![1, 1], 1800), (vec![1, 3], 1200), (vec![2, 1], 2700)] {
+            let run = Run::new("Fair share", 1, true, FairShare::new(5, shares.clone(), GroupPolicy::RoundRobin, 0).unwrap(), &workload);
+            check_invariants(&run, &workload, 0);
+            assert!(completion_time(&run).abs_diff(expected) <= 10, "{:?}: single process of group 1 ended at {}", shares, completion_time(&run));
+        }
+        assert!(completion_time(&Run::new("Rotating", 1, true, rotating::Rotating::new(5, 0), &workload)) > 2600);
+        let settings = SimulationSettings { algorithms: Some(vec!["Fair share".to_owned(), "Rotating".to_owned()]), ..SimulationSettings::default() };
+        let results = simulator::simulate_every(workload, &settings).unwrap();
+        let group = |name: &str| results.iter().find(|(result_name, _)| result_name == name).unwrap().1.get_groups()[&1].clone();
+        assert!(group("Fair share").get_average_waiting_time() < group("Rotating").get_average_waiting_time() - 500f64);
+        assert!((group("Fair share").get_processor_share() - 1f64 / 3f64).abs() < 1e-9);
+        assert!(matches!(FairShare::new(5, vec![1, 0], GroupPolicy::RoundRobin, 0), Err(SimulationError::InvalidParameter(_))));
+        assert!(matches!(FairShare::new(5, vec![1, u32::MAX], GroupPolicy::RoundRobin, 0), Err(SimulationError::InvalidParameter(_))));
+    }
+}
@@ -0,0 +1,206 @@
+use std::str::FromStr;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rand_distr::{Distribution as _, Exp, Pareto};
+
+use crate::cpu_access_manager::{ProcessDescription, SimulationError, Time, DEFAULT_TICKETS};
+use crate::simulator::Processes;
+
+// Every sample is rounded to whole ticks
+#[derive(Debug, Clone)]
+pub enum Distribution {
+    Uniform { minimum: Time, maximum: Time },
+    Exponential { mean: f64 },
+    Pareto { scale: f64, shape: f64 }, // Heavy tailed, scale is the smallest possible value, smaller shape gives longer tail
+    Bimodal { short_mean: f64, long_mean: f64, long_probability: f64 }, // Exponential around one of two means
+}
+
+impl Distribution {
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Distribution::Uniform { minimum, maximum } => minimum <= maximum,
+            Distribution::Exponential { mean } => *mean > 0f64,
+            Distribution::Pareto { scale, shape } => *scale > 0f64 && *shape > 0f64,
+            Distribution::Bimodal { short_mean, long_mean, long_probability } => *short_mean > 0f64 && *long_mean > 0f64 && (0f64..=1f64).contains(long_probability),
+        }
+    }
+
+    pub fn sample<R: Rng>(&self, rng: &mut R) -> Time {
+        let value = match self {
+            Distribution::Uniform { minimum, maximum } => return rng.gen_range(*minimum..=*maximum),
+            Distribution::Exponential { mean } => Exp::new(1f64 / mean).unwrap().sample(rng),
+            Distribution::Pareto { scale, shape } => Pareto::new(*scale, *shape).unwrap().sample(rng),
+            Distribution::Bimodal { short_mean, long_mean, long_probability } => match rng.gen_bool(*long_probability) {
+                true => Exp::new(1f64 / long_mean).unwrap().sample(rng),
+                false => Exp::new(1f64 / short_mean).unwrap().sample(rng),
+            },
+        };
+        value.round().min(Time::MAX as f64) as Time
+    }
+}
+
+fn parse_parameters<T: FromStr>(parameters: &[&str], names: &[&str]) -> Result<Vec<T>, String> {
+    if parameters.len() != names.len() {
+        return Err(format!("Expected parameters: {}", names.join(":")));
+    }
+    parameters.iter().zip(names).map(|(parameter, name)| parameter.trim().parse().map_err(|_| format!("Invalid {}: {}", name, parameter))).collect()
+}
+
+// Kind followed by ':' separated parameters, e.g. "uniform:1:300", "exponential:50", "pareto:10:1.5" or "bimodal:10:200:0.2"
+impl FromStr for Distribution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts: Vec<&str> = s.split(':').collect();
+        let kind = parts.remove(0).trim().to_lowercase();
+        let distribution = match kind.as_str() {
+            "uniform" => {
+                let parameters = parse_parameters(&parts, &["minimum", "maximum"])?;
+                Distribution::Uniform { minimum: parameters[0], maximum: parameters[1] }
+            },
+            "exponential" | "exp" => Distribution::Exponential { mean: parse_parameters(&parts, &["mean"])?[0] },
+            "pareto" => {
+                let parameters = parse_parameters(&parts, &["scale", "shape"])?;
+                Distribution::Pareto { scale: parameters[0], shape: parameters[1] }
+            },
+            "bimodal" => {
+                let parameters = parse_parameters(&parts, &["short_mean", "long_mean", "long_probability"])?;
+                Distribution::Bimodal { short_mean: parameters[0], long_mean: parameters[1], long_probability: parameters[2] }
+            },
+            _ => return Err(format!("Unknown distribution: {} (uniform, exponential, pareto or bimodal)", kind)),
+        };
+        match distribution.is_valid() {
+            true => Ok(distribution),
+            false => Err(format!("Invalid parameters of distribution: {}", s)),
+        }
+    }
+}
+
+// Group of similar jobs, e.g. short interactive ones or long batch ones, share is relative to shares of other classes
+#[derive(Debug, Clone)]
+pub struct JobClass {
+    pub share: f64,
+    pub duration: Distribution,
+}
+
+// Share followed by distribution of duration, e.g. "0.8:exponential:20"
+impl FromStr for JobClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (share, duration) = s.split_once(':').ok_or(format!("Expected share:distribution, got: {}", s))?;
+        let share: f64 = share.trim().parse().map_err(|_| format!("Invalid share: {}", share))?;
+        if share < 0f64 {
+            return Err(format!("Share cannot be negative: {}", share));
+        }
+        Ok(JobClass { share, duration: duration.parse()? })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GeneratorSettings {
+    pub minimum_processes_count: usize,
+    pub maximum_processes_count: usize,
+    pub inter_arrival_time: Distribution, // Exponential inter-arrival time gives Poisson arrivals
+    pub job_classes: Vec<JobClass>,
+    pub lifetime_probability: f64,
+    pub lifetime: Distribution,
+    pub priority_classes_count: u32,
+    pub minimum_tickets: u32,
+    pub maximum_tickets: u32,
+    pub maximum_io_bursts_count: usize,
+    pub io_duration: Distribution,
+    pub groups_count: u32, // Owners of processes, every process gets random one
+    pub seed: Option<u64>, // Without seed every call generates different workload
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        Self { minimum_processes_count: 50, maximum_processes_count: 1000, inter_arrival_time: Distribution::Uniform { minimum: 0, maximum: 100 }, job_classes: vec![JobClass { share: 1f64, duration: Distribution::Uniform { minimum: 1, maximum: 300 } }], lifetime_probability: 0.3, lifetime: Distribution::Uniform { minimum: 1, maximum: 2000 }, priority_classes_count: 4, minimum_tickets: DEFAULT_TICKETS, maximum_tickets: DEFAULT_TICKETS, maximum_io_bursts_count: 0, io_duration: Distribution::Uniform { minimum: 1, maximum: 50 }, groups_count: 1, seed: None }
+    }
+}
+
+impl GeneratorSettings {
+    fn validate(&self) -> Result<(), SimulationError> {
+        if self.minimum_processes_count > self.maximum_processes_count || self.minimum_tickets == 0 || self.minimum_tickets > self.maximum_tickets || self.priority_classes_count == 0 || self.groups_count == 0 {
+            return Err(SimulationError::InvalidParameter("Invalid workload generator ranges"));
+        }
+        if self.job_classes.is_empty() || self.job_classes.iter().any(|class| class.share < 0f64 || !class.duration.is_valid()) || self.job_classes.iter().all(|class| class.share == 0f64) {
+            return Err(SimulationError::InvalidParameter("There has to be at least one job class with positive share and valid duration distribution"));
+        }
+        if !self.inter_arrival_time.is_valid() || !self.lifetime.is_valid() || !self.io_duration.is_valid() || !(0f64..=1f64).contains(&self.lifetime_probability) {
+            return Err(SimulationError::InvalidParameter("Invalid workload generator distribution"));
+        }
+        Ok(())
+    }
+
+    fn select_job_class<R: Rng>(&self, rng: &mut R) -> &JobClass {
+        let mut value = rng.gen_range(0f64..self.job_classes.iter().map(|class| class.share).sum::<f64>());
+        for class in self.job_classes.iter() {
+            if value < class.share {
+                return class;
+            }
+            value -= class.share;
+        }
+        self.job_classes.last().unwrap()
+    }
+}
+
+// Processor and I/O bursts can't be 0, so they are rounded up to 1
+pub fn generate(settings: &GeneratorSettings) -> Result<Processes, SimulationError> {
+    settings.validate()?;
+    let mut rng = match settings.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let processes_count = rng.gen_range(settings.minimum_processes_count..=settings.maximum_processes_count);
+    let mut processes = Vec::with_capacity(processes_count);
+    for _ in 0..processes_count {
+        let wait = settings.inter_arrival_time.sample(&mut rng);
+        let class = settings.select_job_class(&mut rng);
+        let lifetime = match rng.gen_bool(settings.lifetime_probability) {
+            true => Some(settings.lifetime.sample(&mut rng)),
+            false => None,
+        };
+        let description = ProcessDescription::new(class.duration.sample(&mut rng).max(1), lifetime, rng.gen_range(0..settings.priority_classes_count));
+        let io_bursts = (0..rng.gen_range(0..=settings.maximum_io_bursts_count)).map(|_| (settings.io_duration.sample(&mut rng).max(1), class.duration.sample(&mut rng).max(1))).collect();
+        let description = description.with_io_bursts(io_bursts).with_tickets(rng.gen_range(settings.minimum_tickets..=settings.maximum_tickets));
+        // Single group doesn't draw anything, so workloads generated from the same seed stay the same
+        processes.push((wait, match settings.groups_count {
+            1 => description,
+            groups_count => description.with_group(rng.gen_range(0..groups_count)),
+        }));
+    }
+    Ok(Processes::from(processes))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::workload;
+
+    use super::*;
+
+    #[test]
+    fn the_same_seed_gives_the_same_workload() {
+        let settings = GeneratorSettings { maximum_processes_count: 200, maximum_io_bursts_count: 2, maximum_tickets: 300, groups_count: 3, job_classes: vec!["0.8:exponential:20".parse().unwrap(), "0.2:pareto:50:1.5".parse().unwrap()], seed: Some(42), ..GeneratorSettings::default() };
+        let csv = workload::to_csv(&generate(&settings).unwrap());
+        assert_eq!(workload::to_csv(&generate(&settings).unwrap()), csv);
+        assert_ne!(workload::to_csv(&generate(&GeneratorSettings { seed: Some(43), ..settings }).unwrap()), csv);
+        // Saved seeds have to keep reproducing the same workloads, so default settings can't start drawing anything new
+        let pinned = GeneratorSettings { minimum_processes_count: 3, maximum_processes_count: 3, seed: Some(1), ..GeneratorSettings::default() };
+        assert_eq!(workload::to_csv(&generate(&pinned).unwrap()), "wait,duration,lifetime,priority,io_bursts,tickets,group,period\n17,117,,0,,100,0,\n20,59,,1,,100,0,\n31,95,,1,,100,0,\n");
+    }
+
+    #[test]
+    fn invalid_distributions_are_rejected() {
+        for distribution in ["uniform:5:1", "uniform:1", "uniform:a:5", "exponential:0", "exp:-3", "pareto:0:1.5", "pareto:10:0", "bimodal:10:200:1.5", "bimodal:10:200", "normal:5", ""] {
+            assert!(distribution.parse::<Distribution>().is_err(), "{}", distribution);
+        }
+        for distribution in ["uniform:1:300", "Exponential:50", "exp:0.5", "pareto:10:1.5", "bimodal:10:200:0.2", "uniform: 3 : 3"] {
+            assert!(distribution.parse::<Distribution>().is_ok(), "{}", distribution);
+        }
+        assert!("-0.5:exponential:20".parse::<JobClass>().is_err());
+        assert!("0.5".parse::<JobClass>().is_err());
+    }
+}
@@ -0,0 +1,415 @@
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use crate::cpu_access_manager::{CpuAccessManager, ProcessDescription, ProcessStatistics, SimulationError, Time};
+use crate::generator::{self, Distribution, GeneratorSettings, JobClass};
+use crate::simulator::{self, Processes, SimulationSettings};
+use crate::trace::{EventKind, Trace};
+use crate::registry::Registry;
+use crate::{cfs, deadline, fair_share, fcfs, hrrn, mlfq, multicore, periodic, predictive_sjf, priority, proportional_share, rotating, sjf};
+
+// Everything which can be observed after running single scheduler on single workload
+pub(crate) struct Run {
+    name: String,
+    cores_count: usize,
+    is_work_conserving: bool,
+    pub(crate) statistics: Vec<ProcessStatistics>,
+    trace: Trace,
+    working_time: Time,
+    idle_time: Time,
+    context_switches_time: Time,
+    abandoned_count: usize,
+}
+
+impl Run {
+    pub(crate) fn new<T: CpuAccessManager>(name: &str, cores_count: usize, is_work_conserving: bool, mut cpu: T, workload: &Processes) -> Self {
+        cpu.enable_trace();
+        for (wait, description) in workload.iter() {
+            cpu.simulate_n_ticks(*wait);
+            cpu.add_process(description).unwrap();
+        }
+        cpu.simulate_till_end_of_every_process();
+        let trace = cpu.get_trace().clone();
+        let working_time = cpu.get_total_working_time();
+        let idle_time = cpu.get_total_waiting_time();
+        let context_switches_time = cpu.get_context_switches_time();
+        let abandoned_count = cpu.get_abandoned_count().unwrap_or(0);
+        let mut statistics = Box::new(cpu).finalize();
+        statistics.sort_by_key(|process| process.get_id());
+        Self { name: name.to_owned(), cores_count, is_work_conserving, statistics, trace, working_time, idle_time, context_switches_time, abandoned_count }
+    }
+
+    fn get_makespan(&self) -> Time {
+        self.statistics.iter().map(|process| process.get_completion_time().unwrap()).max().unwrap_or(0)
+    }
+
+    // Processor time used by every process, indexed by process id
+    fn get_executed_times(&self) -> Vec<Time> {
+        let mut executed = vec![0; self.statistics.len()];
+        for slice in self.trace.get_slices() {
+            executed[slice.get_process_id() as usize] += slice.get_end() - slice.get_start();
+        }
+        executed
+    }
+}
+
+// Every scheduler with a few different parameters, multiprocessor ones with per-core queues may leave core idle till the next balance
+fn run_every(workload: &Processes, context_switch_cost: Time) -> Vec<Run> {
+    vec![
+        Run::new("FCFS", 1, true, fcfs::FCFS::new(context_switch_cost), workload),
+        Run::new("SJF", 1, true, sjf::SJF::new(context_switch_cost), workload),
+        Run::new("SJF with preemption", 1, true, sjf::SJFWithPreemption::new(context_switch_cost), workload),
+        Run::new("HRRN", 1, true, hrrn::HRRN::new(context_switch_cost), workload),
+        Run::new("Predictive SJF", 1, true, predictive_sjf::PredictiveSJF::new(0.5, 50f64, context_switch_cost).unwrap(), workload),
+        Run::new("Predictive SJF with preemption", 1, true, predictive_sjf::PredictiveSJFWithPreemption::new(0.5, 50f64, context_switch_cost).unwrap(), workload),
+        Run::new("Rotating quant 1", 1, true, rotating::Rotating::new(1, context_switch_cost), workload),
+        Run::new("Rotating quant 7", 1, true, rotating::Rotating::new(7, context_switch_cost), workload),
+        Run::new("Rotating with mean burst quant", 1, true, rotating::AdaptiveRotating::new(rotating::QuantumRule::MeanBurst, 1, 50, context_switch_cost).unwrap(), workload),
+        Run::new("Rotating with median burst quant", 1, true, rotating::AdaptiveRotating::new(rotating::QuantumRule::MedianBurst, 3, 20, context_switch_cost).unwrap(), workload),
+        Run::new("Rotating with target latency", 1, true, rotating::AdaptiveRotating::new(rotating::QuantumRule::TargetLatency(30), 2, 10, context_switch_cost).unwrap(), workload),
+        Run::new("MLFQ", 1, true, mlfq::MLFQ::new(vec![2, 5, 20], Some(100), context_switch_cost).unwrap(), workload),
+        Run::new("MLFQ without boost", 1, true, mlfq::MLFQ::new(vec![3], None, context_switch_cost).unwrap(), workload),
+        Run::new("Priority", 1, true, priority::Priority::new(None, context_switch_cost).unwrap(), workload),
+        Run::new("Priority with aging", 1, true, priority::Priority::new(Some(10), context_switch_cost).unwrap(), workload),
+        Run::new("Priority with preemption", 1, true, priority::PriorityWithPreemption::new(None, context_switch_cost).unwrap(), workload),
+        Run::new("Priority with preemption and aging", 1, true, priority::PriorityWithPreemption::new(Some(10), context_switch_cost).unwrap(), workload),
+        Run::new("CFS", 1, true, cfs::CFS::new(20, 4, context_switch_cost).unwrap(), workload),
+        Run::new("Lottery", 1, true, proportional_share::Lottery::new(5, Some(42), context_switch_cost).unwrap(), workload),
+        Run::new("Stride", 1, true, proportional_share::Stride::new(5, context_switch_cost).unwrap(), workload),
+        Run::new("Fair share", 1, true, fair_share::FairShare::new(5, Vec::new(), fair_share::GroupPolicy::RoundRobin, context_switch_cost).unwrap(), workload),
+        Run::new("Fair share with shares and SJF", 1, true, fair_share::FairShare::new(3, vec![3, 1, 2], fair_share::GroupPolicy::SJF, context_switch_cost).unwrap(), workload),
+        Run::new("Fair share with FCFS", 1, true, fair_share::FairShare::new(7, vec![1, 5], fair_share::GroupPolicy::FCFS, context_switch_cost).unwrap(), workload),
+        Run::new("Fair share with priority", 1, true, fair_share::FairShare::new(4, Vec::new(), fair_share::GroupPolicy::Priority, context_switch_cost).unwrap(), workload),
+        Run::new("EDF", 1, true, deadline::EDF::new(false, context_switch_cost), workload),
+        Run::new("EDF abandoning hopeless", 1, true, deadline::EDF::new(true, context_switch_cost), workload),
+        Run::new("LLF", 1, true, deadline::LLF::new(5, false, context_switch_cost).unwrap(), workload),
+        Run::new("LLF abandoning hopeless", 1, true, deadline::LLF::new(5, true, context_switch_cost).unwrap(), workload),
+        Run::new("Rate monotonic", 1, true, periodic::FixedPriority::new(periodic::PriorityAssignment::RateMonotonic, context_switch_cost), workload),
+        Run::new("Deadline monotonic", 1, true, periodic::FixedPriority::new(periodic::PriorityAssignment::DeadlineMonotonic, context_switch_cost), workload),
+        Run::new("SMP global queue single core", 1, true, multicore::MultiCore::new(1, multicore::ReadyQueue::Global, 5, context_switch_cost).unwrap(), workload),
+        Run::new("SMP global queue", 3, true, multicore::MultiCore::new(3, multicore::ReadyQueue::Global, 5, context_switch_cost).unwrap(), workload),
+        Run::new("SMP per-core queues", 3, false, multicore::MultiCore::new(3, multicore::ReadyQueue::PerCore { balance_period: 20 }, 5, context_switch_cost).unwrap(), workload),
+    ]
+}
+
+pub(crate) fn check_invariants(run: &Run, workload: &Processes, context_switch_cost: Time) {
+    let name = &run.name;
+    let descriptions: Vec<&ProcessDescription> = workload.iter().map(|(_, description)| description).collect();
+
+    // Every process is finalized exactly once and in finished state
+    assert_eq!(run.statistics.len(), descriptions.len(), "{}: wrong number of finalized processes", name);
+    for (id, process) in run.statistics.iter().enumerate() {
+        assert_eq!(process.get_id() as usize, id, "{}: process finalized more than once or never", name);
+        assert!(process.get_completion_time().is_some() && process.is_successful().is_some(), "{}: P{} finalized before finishing", name, id);
+    }
+
+    let executed = run.get_executed_times();
+    let mut abandoned_count = 0;
+    for (id, process) in run.statistics.iter().enumerate() {
+        let completion = process.get_completion_time().unwrap();
+        let description = descriptions[id];
+        assert_eq!(process.get_initial_time(), description.get_total_duration(), "{}: P{} has wrong duration", name, id);
+        assert!(executed[id] <= process.get_initial_time(), "{}: P{} executed longer than its duration", name, id);
+        assert!(completion >= process.get_creation_time() + executed[id] + process.get_io_time(), "{}: P{} completed before it could do its work", name, id);
+        match process.is_successful().unwrap() {
+            true => {
+                assert_eq!(executed[id], process.get_initial_time(), "{}: P{} completed without doing its work", name, id);
+                assert!(process.get_io_time() >= description.get_io_bursts().iter().map(|(io_time, _)| io_time).sum::<Time>(), "{}: P{} skipped I/O", name, id);
+                if let Some(lifetime) = description.get_lifetime() {
+                    assert!(completion <= process.get_creation_time() + lifetime, "{}: P{} completed after its lifetime", name, id);
+                }
+            },
+            false => {
+                assert!(executed[id] < process.get_initial_time(), "{}: P{} failed after doing its whole work", name, id);
+                match description.get_lifetime() {
+                    Some(lifetime) if completion >= process.get_creation_time() + lifetime => (),
+                    _ => abandoned_count += 1,
+                }
+            },
+        }
+        if let Some(response_time) = process.get_response_time() {
+            assert!(process.get_creation_time() + response_time + executed[id] <= completion, "{}: P{} has wrong response time", name, id);
+        }
+    }
+    assert!(abandoned_count <= run.abandoned_count, "{}: processes failed before their lifetime without being abandoned", name);
+
+    // Trace agrees with statistics
+    let mut arrivals = vec![0; descriptions.len()];
+    let mut finishes = vec![0; descriptions.len()];
+    for event in run.trace.get_events() {
+        let process = &run.statistics[event.get_process_id() as usize];
+        match event.get_kind() {
+            EventKind::Arrival => {
+                arrivals[process.get_id() as usize] += 1;
+                assert_eq!(event.get_time(), process.get_creation_time(), "{}: P{} arrived at wrong time", name, process.get_id());
+            },
+            EventKind::Completion | EventKind::LifetimeExpiry | EventKind::Abandonment => {
+                finishes[process.get_id() as usize] += 1;
+                assert_eq!(Some(event.get_time()), process.get_completion_time(), "{}: P{} finished at wrong time", name, process.get_id());
+            },
+            EventKind::Dispatch | EventKind::Preemption | EventKind::IoBlock | EventKind::IoCompletion => assert!(event.get_time() >= process.get_creation_time() && event.get_time() <= process.get_completion_time().unwrap(), "{}: P{} has event outside its lifespan", name, process.get_id()),
+        }
+    }
+    assert!(arrivals.iter().chain(finishes.iter()).all(|count| *count == 1), "{}: process arrived or finished more than once", name);
+    for slice in run.trace.get_slices() {
+        let process = &run.statistics[slice.get_process_id() as usize];
+        assert!(slice.get_start() >= process.get_creation_time() && slice.get_end() <= process.get_completion_time().unwrap(), "{}: P{} ran outside its lifespan", name, process.get_id());
+        assert!(slice.get_core() < run.cores_count, "{}: P{} ran on nonexistent core", name, process.get_id());
+    }
+
+    // Work and idle time are accounted exactly
+    assert_eq!(executed.iter().sum::<Time>(), run.working_time, "{}: working time differs from time used by processes", name);
+    if run.cores_count == 1 {
+        assert_eq!(run.working_time + run.idle_time + run.context_switches_time, run.get_makespan(), "{}: working, idle and switching time don't add up to makespan", name);
+    }
+
+    // Processor is never idle while there are ready processes, context switches are not visible in trace, so they can't be checked
+    if run.is_work_conserving && context_switch_cost == 0 {
+        check_work_conservation(run);
+    }
+}
+
+// Number of running processes has to be equal to the number of ready processes, limited by cores count
+fn check_work_conservation(run: &Run) {
+    let mut changes: Vec<(Time, i64, i64)> = Vec::new(); // Time, change of ready processes count, change of running processes count
+    for event in run.trace.get_events() {
+        match event.get_kind() {
+            EventKind::Arrival | EventKind::IoCompletion => changes.push((event.get_time(), 1, 0)),
+            EventKind::Completion | EventKind::LifetimeExpiry | EventKind::Abandonment | EventKind::IoBlock => changes.push((event.get_time(), -1, 0)),
+            EventKind::Dispatch | EventKind::Preemption => (),
+        }
+    }
+    for slice in run.trace.get_slices().iter().filter(|slice| slice.get_start() != slice.get_end()) {
+        changes.push((slice.get_start(), 0, 1));
+        changes.push((slice.get_end(), 0, -1));
+    }
+    changes.sort_by_key(|(time, _, _)| *time);
+    let (mut ready, mut running) = (0i64, 0i64);
+    for (index, (time, ready_change, running_change)) in changes.iter().enumerate() {
+        ready += ready_change;
+        running += running_change;
+        if changes.get(index + 1).is_some_and(|(next_time, _, _)| next_time != time) {
+            assert!(running <= ready, "{}: more processes running than ready at {}", run.name, time);
+            assert_eq!(running, ready.min(run.cores_count as i64), "{}: processor idle while processes are ready at {}", run.name, time);
+        }
+    }
+    assert_eq!((ready, running), (0, 0), "{}: processes left ready or running after the end", run.name);
+}
+
+fn random_distribution(rng: &mut StdRng, mean: Time) -> Distribution {
+    match rng.gen_range(0..4) {
+        0 => Distribution::Uniform { minimum: 0, maximum: 2 * mean },
+        1 => Distribution::Exponential { mean: mean as f64 },
+        2 => Distribution::Pareto { scale: (mean / 4).max(1) as f64, shape: 1.2 },
+        _ => Distribution::Bimodal { short_mean: (mean / 8).max(1) as f64, long_mean: mean as f64, long_probability: 0.2 },
+    }
+}
+
+// Mixes zero gaps, heavy tailed and huge bursts, lifetimes of 0 and 1 and I/O
+pub(crate) fn random_workload(seed: u64) -> Processes {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let huge = rng.gen_bool(0.1);
+    let inter_arrival_mean = rng.gen_range(1..50);
+    let duration_mean = rng.gen_range(1..100);
+    let settings = GeneratorSettings {
+        minimum_processes_count: 1,
+        maximum_processes_count: match huge {
+            true => 3,
+            false => 40,
+        },
+        inter_arrival_time: match rng.gen_range(0..3) {
+            0 => Distribution::Uniform { minimum: 0, maximum: 0 },
+            _ => random_distribution(&mut rng, inter_arrival_mean),
+        },
+        job_classes: match huge {
+            true => vec![JobClass { share: 1f64, duration: Distribution::Uniform { minimum: 50_000, maximum: 500_000 } }],
+            false => vec![JobClass { share: 1f64, duration: random_distribution(&mut rng, duration_mean) }, JobClass { share: rng.gen_range(0f64..1f64), duration: Distribution::Uniform { minimum: 1, maximum: 1 } }],
+        },
+        lifetime_probability: rng.gen_range(0f64..1f64),
+        lifetime: match rng.gen_range(0..3) {
+            0 => Distribution::Uniform { minimum: 0, maximum: 1 },
+            _ => random_distribution(&mut rng, duration_mean * 4),
+        },
+        priority_classes_count: rng.gen_range(1..5),
+        minimum_tickets: 1,
+        maximum_tickets: rng.gen_range(1..200),
+        maximum_io_bursts_count: rng.gen_range(0..4),
+        io_duration: random_distribution(&mut rng, duration_mean),
+        seed: Some(rng.gen()),
+        groups_count: rng.gen_range(1..4),
+    };
+    generator::generate(&settings).unwrap()
+}
+
+fn edge_case_workloads() -> Vec<Processes> {
+    vec![
+        Processes::from(vec![(0, 1, None)]),
+        Processes::from(vec![(0, 1, Some(0))]),
+        Processes::from(vec![(0, 1, Some(1)), (0, 1, Some(1)), (0, 1, Some(1))]),
+        Processes::from(vec![(0, 5, Some(1)), (0, 5, Some(1)), (0, 5, Some(1)), (0, 5, None)]),
+        Processes::from(vec![(10, 3, None), (0, 3, None), (100, 3, None)]),
+        Processes::from(vec![(0, 1_000_000, None), (0, 1, None), (500_000, 1, Some(1))]),
+        Processes::from((0..30).map(|id| (0, 1 + id % 3, Some(id % 4), (id % 3) as u32)).collect::<Vec<_>>()),
+        Processes::from(vec![(0, ProcessDescription::new(1, None, 0).with_io_bursts(vec![(1, 1), (1, 1)])), (0, ProcessDescription::new(2, Some(3), 1).with_io_bursts(vec![(5, 1)])), (0, ProcessDescription::new(3, None, 2).with_tickets(1))]),
+    ]
+}
+
+#[test]
+fn invariants_hold_on_random_workloads() {
+    for seed in 0..50 {
+        let workload = random_workload(seed);
+        for context_switch_cost in [0, 2] {
+            for run in run_every(&workload, context_switch_cost) {
+                check_invariants(&run, &workload, context_switch_cost);
+            }
+        }
+    }
+}
+
+#[test]
+fn invariants_hold_on_edge_case_workloads() {
+    for workload in edge_case_workloads() {
+        for context_switch_cost in [0, 1, 7] {
+            for run in run_every(&workload, context_switch_cost) {
+                check_invariants(&run, &workload, context_switch_cost);
+            }
+        }
+    }
+}
+
+// Single process can only be run whenever it is ready, so every scheduler has to give the same result
+#[test]
+fn schedulers_agree_on_single_process() {
+    let mut rng = StdRng::seed_from_u64(17);
+    for _ in 0..50 {
+        let description = ProcessDescription::new(rng.gen_range(1..500), rng.gen_bool(0.5).then(|| rng.gen_range(0..600)), rng.gen_range(0..4)).with_io_bursts((0..rng.gen_range(0..3)).map(|_| (rng.gen_range(1..50), rng.gen_range(1..100))).collect());
+        let workload = Processes::from(vec![(rng.gen_range(0..100), description)]);
+        let runs = run_every(&workload, 0);
+        let expected = &runs[0].statistics[0];
+        // Abandoning hopeless process early is the whole point of some schedulers
+        for run in runs.iter().filter(|run| run.abandoned_count == 0) {
+            let process = &run.statistics[0];
+            assert_eq!((process.get_completion_time(), process.is_successful(), process.get_response_time()), (expected.get_completion_time(), expected.is_successful(), expected.get_response_time()), "{} differs from FCFS", run.name);
+        }
+    }
+}
+
+// Idle processor still moves the clock, so process arriving after idle gap starts at its arrival (it used to be created at 5 and completed at 10)
+#[test]
+fn idle_gap_moves_clock_forward() {
+    let workload = Processes::from(vec![(0, 5, None), (20, 5, None)]);
+    for run in run_every(&workload, 0) {
+        assert_eq!(run.statistics.iter().map(|process| process.get_completion_time()).collect::<Vec<_>>(), [Some(5), Some(25)], "{}", run.name);
+        assert_eq!(run.trace.get_slices().iter().map(|slice| (slice.get_process_id(), slice.get_start(), slice.get_end())).collect::<Vec<_>>(), [(0, 0, 5), (1, 20, 25)], "{}", run.name);
+        if run.cores_count == 1 {
+            assert_eq!(run.idle_time, 15, "{}", run.name);
+        }
+    }
+}
+
+// Without I/O and lifetimes processor is busy during the same periods for every work conserving scheduler
+#[test]
+fn work_conserving_schedulers_agree_on_makespan() {
+    for seed in 0..20 {
+        let mut workload = random_workload(seed).iter().map(|(wait, description)| (*wait, description.get_duration(), None, description.get_priority())).collect::<Vec<_>>();
+        workload.truncate(20);
+        let workload = Processes::from(workload);
+        let runs: Vec<Run> = run_every(&workload, 0).into_iter().filter(|run| run.cores_count == 1).collect();
+        for run in runs.iter() {
+            assert_eq!((run.get_makespan(), run.idle_time), (runs[0].get_makespan(), runs[0].idle_time), "{} differs from FCFS", run.name);
+        }
+    }
+}
+
+// With equal bursts non-preemptive schedulers can only differ in order of processes, not in completion times
+#[test]
+fn non_preemptive_schedulers_agree_on_equal_bursts() {
+    for duration in [1, 5, 40] {
+        let workload = Processes::from((0..10).map(|id| (id % 3, duration, None, (id % 2) as u32)).collect::<Vec<_>>());
+        let completion_times = |run: Run| {
+            let mut times: Vec<Time> = run.statistics.iter().map(|process| process.get_completion_time().unwrap()).collect();
+            times.sort_unstable();
+            times
+        };
+        let expected = completion_times(Run::new("FCFS", 1, true, fcfs::FCFS::new(0), &workload));
+        assert_eq!(completion_times(Run::new("SJF", 1, true, sjf::SJF::new(0), &workload)), expected);
+        assert_eq!(completion_times(Run::new("HRRN", 1, true, hrrn::HRRN::new(0), &workload)), expected);
+        assert_eq!(completion_times(Run::new("Priority", 1, true, priority::Priority::new(Some(3), 0).unwrap(), &workload)), expected);
+        assert_eq!(completion_times(Run::new("Predictive SJF", 1, true, predictive_sjf::PredictiveSJF::new(0.5, 50f64, 0).unwrap(), &workload)), expected);
+    }
+}
+
+// Quantum longer than every burst makes round robin with FIFO queue first come first served, also with I/O and lifetimes
+#[test]
+fn round_robin_with_long_quantum_matches_fcfs() {
+    for seed in 0..30 {
+        let workload = random_workload(seed);
+        let longest_burst = workload.iter().flat_map(|(_, description)| [description.get_duration()].into_iter().chain(description.get_io_bursts().iter().map(|(_, duration)| *duration))).max().unwrap();
+        let expected = Run::new("FCFS", 1, true, fcfs::FCFS::new(0), &workload);
+        let run = Run::new("SMP global queue", 1, true, multicore::MultiCore::new(1, multicore::ReadyQueue::Global, longest_burst, 0).unwrap(), &workload);
+        for (process, expected_process) in run.statistics.iter().zip(expected.statistics.iter()) {
+            assert_eq!((process.get_completion_time(), process.is_successful()), (expected_process.get_completion_time(), expected_process.is_successful()), "P{} differs", process.get_id());
+        }
+    }
+}
+
+// Workloads longer than 32 bits of ticks are simulated, the ones which don't fit in 64 bits are rejected before anything runs
+#[test]
+fn long_workloads_dont_overflow() {
+    let long_burst = u32::MAX as Time + 10;
+    let workload = Processes::from(vec![(0, long_burst, None), (u32::MAX as Time, long_burst, Some(long_burst)), (0, 3, None)]);
+    for run in [Run::new("FCFS", 1, true, fcfs::FCFS::new(2), &workload), Run::new("SJF with preemption", 1, true, sjf::SJFWithPreemption::new(2), &workload), Run::new("EDF", 1, true, deadline::EDF::new(false, 2), &workload)] {
+        check_invariants(&run, &workload, 2);
+        assert!(run.get_makespan() > 2 * u32::MAX as Time, "{}: makespan is too short", run.name);
+    }
+    let overflowing = Processes::from(vec![(0, Time::MAX / 2, None), (Time::MAX / 2, 2, None)]);
+    assert_eq!(simulator::simulate_every(overflowing, &SimulationSettings::default()).err(), Some(SimulationError::TimeOverflow));
+}
+
+#[test]
+fn invalid_input_is_reported_as_error() {
+    let settings = SimulationSettings::default();
+    assert!(matches!(simulator::simulate_every(Processes::from(vec![(0, 5, None), (1, 0, None)]), &settings), Err(SimulationError::InvalidProcess(_))));
+    assert!(matches!(simulator::simulate_every(Processes::from(vec![(0, ProcessDescription::new(5, None, 0).with_io_bursts(vec![(0, 1)]))]), &settings), Err(SimulationError::InvalidProcess(_))));
+    assert!(matches!(simulator::simulate_every(Processes::from(vec![(0, ProcessDescription::new(5, None, 0).with_tickets(0))]), &settings), Err(SimulationError::InvalidProcess(_))));
+    assert!(matches!(simulator::simulate_every(Processes::from(vec![(0, 5, None)]), &SimulationSettings { quant: 0, ..settings.clone() }), Err(SimulationError::InvalidParameter(_))));
+    assert!(matches!(simulator::simulate_every(Processes::from(vec![(0, 5, None)]), &SimulationSettings { mlfq_quantum_times: Vec::new(), ..settings.clone() }), Err(SimulationError::InvalidParameter(_))));
+    assert!(matches!(multicore::MultiCore::new(0, multicore::ReadyQueue::Global, 5, 0), Err(SimulationError::InvalidParameter(_))));
+    let mut cpu = fcfs::FCFS::new(0);
+    assert_eq!(cpu.add_process(&ProcessDescription::new(Time::MAX, None, 0)), Ok(0));
+    assert_eq!(cpu.add_process(&ProcessDescription::new(1, None, 0)), Err(SimulationError::TimeOverflow));
+    assert_eq!(cpu.add_process(&ProcessDescription::new(0, None, 0)), Err(SimulationError::InvalidProcess("Process can't have duration time of 0")));
+}
+
+// Schedule can't depend on how simulation is split into calls, so running every gap between arrivals tick by tick has to give the same statistics
+#[test]
+fn simulating_tick_by_tick_matches_whole_gaps() {
+    let process_key = |process: &ProcessStatistics| (process.get_id(), process.get_completion_time(), process.is_successful(), process.get_response_time(), process.get_waiting_time(), process.get_call_count(), process.get_io_time());
+    for seed in 0..20 {
+        let workload = random_workload(seed);
+        if workload.iter().map(|(wait, description)| wait + description.get_total_duration()).sum::<Time>() > 20_000 {
+            continue;
+        }
+        let settings = SimulationSettings { context_switch_cost: seed % 3, lottery_seed: Some(seed), ..SimulationSettings::default() };
+        let registry = Registry::new();
+        for name in registry.get_names() {
+            let mut results = Vec::new();
+            for is_tick_by_tick in [false, true] {
+                let mut cpu = registry.create(name, &settings).unwrap().unwrap();
+                for (wait, description) in workload.iter() {
+                    match is_tick_by_tick {
+                        true => (0..*wait).for_each(|_| cpu.simulate_one_tick()),
+                        false => cpu.simulate_n_ticks(*wait),
+                    }
+                    cpu.add_process(description).unwrap();
+                }
+                cpu.simulate_till_end_of_every_process();
+                let totals = (cpu.get_total_working_time(), cpu.get_total_waiting_time(), cpu.get_context_switches_count(), cpu.get_context_switches_time());
+                let mut statistics: Vec<_> = cpu.finalize().iter().map(process_key).collect();
+                statistics.sort_unstable();
+                results.push((totals, statistics));
+            }
+            assert_eq!(results[0], results[1], "{}: seed {}", name, seed);
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use std::collections::VecDeque;
+
+use crate::cpu_access_manager::Time;
+
+// Single I/O device serving blocked processes in FCFS order, T is whatever scheduler needs to put process back to its ready queue
+#[derive(Debug, Clone)]
+pub struct IoDevice<T> {
+    queue: VecDeque<(T, Time)>,
+    current_time: Time,
+    working_time: Time,
+}
+
+impl<T> Default for IoDevice<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> IoDevice<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self { queue: VecDeque::new(), current_time: 0, working_time: 0 }
+    }
+
+    #[inline]
+    pub fn is_working(&self) -> bool {
+        !self.queue.is_empty()
+    }
+
+    pub fn block(&mut self, current_time: Time, item: T, io_time: Time) {
+        if self.queue.is_empty() {
+            self.current_time = current_time;
+        }
+        self.queue.push_back((item, io_time));
+    }
+
+    #[inline]
+    pub fn get_time_to_next_completion(&self) -> Option<Time> {
+        self.queue.front().map(|(_, io_time_left)| *io_time_left)
+    }
+
+    // Limits time of processor work, so it ends when the next I/O burst is completed, device may not be advanced to current time yet
+    #[inline]
+    pub fn limit(&self, current_time: Time, time: Time) -> Time {
+        self.get_time_to_next_completion().map_or(time, |io_time_left| io_time_left.saturating_sub(current_time - self.current_time).min(time))
+    }
+
+    // Returns items which I/O bursts have ended together with their completion time
+    pub fn advance_to(&mut self, time: Time) -> Vec<(T, Time)> {
+        let mut ans = Vec::new();
+        while self.current_time < time {
+            match self.queue.front_mut() {
+                Some((_, io_time_left)) => {
+                    let worked_time = (*io_time_left).min(time - self.current_time);
+                    *io_time_left -= worked_time;
+                    self.current_time += worked_time;
+                    self.working_time += worked_time;
+                    if *io_time_left == 0 {
+                        ans.push((self.queue.pop_front().unwrap().0, self.current_time));
+                    }
+                },
+                None => self.current_time = time,
+            }
+        }
+        ans
+    }
+
+    #[inline]
+    pub fn get_working_time(&self) -> Time {
+        self.working_time
+    }
+}
@@ -0,0 +1,39 @@
+// Simulation of processor scheduling as a library, the zad1 binary (interactive menu and command line) is built on it.
+// Schedulers implement CpuAccessManager, so schedulers defined in other crates can be simulated, registered by name
+// in a Registry and compared with the built-in ones, see examples directory.
+
+pub mod cpu_access_manager;
+pub mod fcfs;
+pub mod sjf;
+pub mod hrrn;
+pub mod predictive_sjf;
+pub mod rotating;
+pub mod cfs;
+pub mod proportional_share;
+pub mod fair_share;
+pub mod deadline;
+pub mod periodic;
+pub mod schedulability;
+pub mod mlfq;
+pub mod multicore;
+pub mod priority;
+pub mod ring_queue;
+pub mod simulator;
+pub mod registry;
+pub mod trace;
+pub mod io_device;
+pub mod workload;
+pub mod generator;
+pub mod report;
+pub mod experiment;
+#[cfg(test)]
+mod invariant_tests;
+
+extern crate rand;
+extern crate rand_distr;
+
+pub use cpu_access_manager::{ContextSwitch, CpuAccessManager, Process, ProcessDescription, ProcessStatistics, SimulationError, Time};
+pub use io_device::IoDevice;
+pub use registry::Registry;
+pub use simulator::{Processes, Simulation, SimulationSettings, SimulationStatistics};
+pub use trace::Trace;
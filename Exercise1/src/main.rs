@@ -1,19 +1,16 @@
 use std::str::FromStr;
 
+use clap::Parser;
 use num_format::{ToFormattedString, Locale};
-use rand::Rng;
-use simulator::SimulationStatistics;
+use zad1::{cpu_access_manager, experiment, fair_share, generator, periodic, report, schedulability, simulator, workload, Registry, SimulationStatistics};
 
-mod cpu_access_manager;
-mod fcfs;
-mod sjf;
-mod rotating;
-mod loop_list;
-mod simulator;
+mod cli;
+mod visualizer;
 
-extern crate rand;
 extern crate dialoguer;
 extern crate num_format;
+extern crate clap;
+extern crate ratatui;
 
 fn input<T, S>(prompt: S) -> T
     where
@@ -41,6 +38,10 @@ fn input_with_default<T, S>(prompt: S, default: T) -> T
     }
 }
 
+fn print_time_statistics(name: &str, statistics: &simulator::TimeStatistics) {
+    println!("{}: average: {:.2}, p50: {}, p90: {}, p99: {}, max: {}", name, statistics.get_average(), statistics.get_p50().to_formatted_string(&Locale::fr), statistics.get_p90().to_formatted_string(&Locale::fr), statistics.get_p99().to_formatted_string(&Locale::fr), statistics.get_max().to_formatted_string(&Locale::fr));
+}
+
 fn print_results(results: Vec<(String, SimulationStatistics)>) {
     for (name, result) in results {
         println!("{}", name);
@@ -50,63 +51,443 @@ fn print_results(results: Vec<(String, SimulationStatistics)>) {
         println!("Average call count: {:.2}", result.get_average_call_count());
         println!("Average call count of successful processes: {:.2}", result.get_average_call_count_of_successful_processes());
         println!("Average partial waiting time: {:.2}", result.get_average_partial_waiting_time());
+        print_time_statistics("Waiting time", result.get_waiting_time());
+        print_time_statistics("Turnaround time", result.get_turnaround_time());
+        print_time_statistics("Response time", result.get_response_time());
         println!("Processes with lifetime count: {}", result.get_lifetime_processes_count().to_formatted_string(&Locale::fr));
         println!("Processes ended successfully: {}", result.get_successful_processes_count().to_formatted_string(&Locale::fr));
-        let successful_lifetime_processes = result.get_successful_processes_count() - (result.get_processes_count() - result.get_lifetime_processes_count());
-        println!("Lifetime processes ended successfully: {}", successful_lifetime_processes.to_formatted_string(&Locale::fr));
-        println!("Successful lifetime processes ratio: {:.2}", successful_lifetime_processes as f64 / result.get_lifetime_processes_count() as f64);
+        println!("Lifetime processes ended successfully: {}", result.get_successful_lifetime_processes_count().to_formatted_string(&Locale::fr));
+        if let Some(deadline_hit_ratio) = result.get_deadline_hit_ratio() {
+            println!("Successful lifetime processes ratio: {:.2}", deadline_hit_ratio);
+        }
+        if let Some(abandoned_count) = result.get_abandoned_count() {
+            println!("Abandoned processes: {}", abandoned_count.to_formatted_string(&Locale::fr));
+        }
+        println!("Context switches count: {}", result.get_context_switches_count().to_formatted_string(&Locale::fr));
+        println!("Time spent on context switches: {}", result.get_context_switches_time().to_formatted_string(&Locale::fr));
+        println!("Effective utilization: {:.2}", result.get_effective_utilization());
+        println!("CPU utilization: {:.2}", result.get_cpu_utilization());
+        println!("Processor working time: {}, idle time: {}", result.get_working_time().to_formatted_string(&Locale::fr), result.get_idle_time().to_formatted_string(&Locale::fr));
+        println!("Makespan: {}, throughput: {:.4} processes per tick", result.get_makespan().to_formatted_string(&Locale::fr), result.get_throughput());
+        println!("I/O device utilization: {:.2}", result.get_io_utilization());
+        if let Some(prediction_error) = result.get_prediction_error() {
+            println!("Average burst prediction error: {:.2} (estimates improve only for processes with I/O bursts)", prediction_error);
+        }
+        if let Some(fairness_deviation) = result.get_fairness_deviation() {
+            println!("Deviation from ideal processor share: {:.2}", fairness_deviation);
+        }
+        print_shares(result.get_processes_shares());
+        if result.get_cores_utilization().len() > 1 {
+            for (core, utilization) in result.get_cores_utilization().iter().enumerate() {
+                println!("Core {} utilization: {:.2}", core, utilization);
+            }
+            println!("Migrations count: {}", result.get_migrations_count().to_formatted_string(&Locale::fr));
+        }
+        if result.get_priority_classes().len() > 1 {
+            for (priority, class) in result.get_priority_classes() {
+                println!("Priority {}: processes count: {}, average waiting time: {:.2}, longest waiting time: {}", priority, class.get_processes_count().to_formatted_string(&Locale::fr), class.get_average_waiting_time(), class.get_longest_waiting_time().to_formatted_string(&Locale::fr));
+            }
+        }
+        if result.get_groups().len() > 1 {
+            for (group, statistics) in result.get_groups() {
+                println!("Group {}: processes count: {}, processor share: {:.3}, average waiting time: {:.2}, longest waiting time: {}", group, statistics.get_processes_count().to_formatted_string(&Locale::fr), statistics.get_processor_share(), statistics.get_average_waiting_time(), statistics.get_longest_waiting_time().to_formatted_string(&Locale::fr));
+            }
+        }
         println!("==================")
     }
 }
 
-fn random_test_menu(quant: u32) {
-    let minimum_number_of_processes_in_test: usize = input_with_default("Minimum number of processes in test", 50);
-    let maximum_number_of_processes_in_test: usize = input_with_default("Maximum number of processes in test", 1000).max(minimum_number_of_processes_in_test);
-    let minimum_process_duration: u32 = input_with_default("Minimum process duration (cannot be 0, it will be incremented if so)", 1).max(1);
-    let maximum_process_duration: u32 = input_with_default("Maximum process duration", 300);
-    let minimum_time_between_new_processes: u32 = input_with_default("Minimum time between new processes", 0);
-    let maximum_time_between_new_processes: u32 = input_with_default("Maximum time between new processes", 100).max(minimum_time_between_new_processes);
-    let lifetime_probability: u32 = input_with_default("Life time probability per mil [0;1000]", 300);
-    let minimum_lifetime = input_with_default("Minimum lifetime", 1);
-    let maximum_lifetime = input_with_default("Maximum lifetime", 2000);
-
-    print_results(simulator::simulate_every(simulator::Processes::from({
-        let mut rng = rand::thread_rng();
-        let process_count = rng.gen_range(minimum_number_of_processes_in_test..=maximum_number_of_processes_in_test);
-        let mut processes_list = Vec::with_capacity(process_count);
-        for _ in 0..process_count {
-            processes_list.push((rng.gen_range(minimum_time_between_new_processes..=maximum_time_between_new_processes), rng.gen_range(minimum_process_duration..=maximum_process_duration), match rng.gen_range(0..1000).cmp(&lifetime_probability) {
-                std::cmp::Ordering::Less => Some(rng.gen_range(minimum_lifetime..=maximum_lifetime)),
-                _ => None,
-            }));
+// Shares of every process are printed only for small tests, otherwise they are averaged over processes with the same weight
+fn print_shares(shares: &[cpu_access_manager::ProcessShare]) {
+    if shares.len() <= 20 {
+        for share in shares {
+            println!("Process {} (weight {}): requested share: {:.3}, achieved share: {:.3}", share.get_process_id(), share.get_weight(), share.get_requested_share(), share.get_achieved_share());
+        }
+        return;
+    }
+    let mut weights = std::collections::BTreeMap::<u64, (usize, f64, f64)>::new();
+    for share in shares {
+        let entry = weights.entry(share.get_weight()).or_default();
+        entry.0 += 1;
+        entry.1 += share.get_requested_share();
+        entry.2 += share.get_achieved_share();
+    }
+    for (weight, (count, requested_share, achieved_share)) in weights {
+        println!("Weight {}: processes count: {}, average requested share: {:.3}, average achieved share: {:.3}", weight, count.to_formatted_string(&Locale::fr), requested_share / count as f64, achieved_share / count as f64);
+    }
+}
+
+fn export_traces(results: &[(String, SimulationStatistics)], directory: &str) -> std::io::Result<()> {
+    std::fs::create_dir_all(directory)?;
+    for (name, result) in results {
+        if let Some(trace) = result.get_trace() {
+            let path = std::path::Path::new(directory).join(name.to_lowercase().replace(' ', "_"));
+            for (extension, content) in [("csv", trace.to_csv()), ("txt", trace.to_ascii_gantt(120)), ("svg", trace.to_svg())] {
+                std::fs::write(path.with_extension(extension), content)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run_simulations(process_list: simulator::Processes, settings: &simulator::SimulationSettings) {
+    let results = match simulator::simulate_every(process_list, settings) {
+        Ok(results) => results,
+        Err(err) => {
+            println!("Simulation failed: {}", err);
+            return;
+        },
+    };
+    if let Some(directory) = &settings.trace_directory {
+        match export_traces(&results, directory) {
+            Ok(()) => println!("Traces saved to {}", directory),
+            Err(err) => println!("Cannot save traces: {}", err),
+        }
+    }
+    print_results(results);
+}
+
+// Prompts for parameters of distribution, default values are used for distribution of the same kind
+fn distribution_menu(name: &str, default: generator::Distribution, allow_zero: bool) -> generator::Distribution {
+    let minimum_value = !allow_zero as cpu_access_manager::Time;
+    let default_index = match default {
+        generator::Distribution::Uniform { .. } => 0,
+        generator::Distribution::Exponential { .. } => 1,
+        generator::Distribution::Pareto { .. } => 2,
+        generator::Distribution::Bimodal { .. } => 3,
+    };
+    let distribution = match dialoguer::Select::new().with_prompt(format!("{} distribution", name)).items(&["Uniform", "Exponential", "Pareto", "Bimodal"]).default(default_index).interact() {
+        Ok(1) => {
+            let default_mean = match default {
+                generator::Distribution::Exponential { mean } => mean,
+                _ => 50f64,
+            };
+            generator::Distribution::Exponential { mean: input_with_default(format!("{} mean", name), default_mean) }
+        },
+        Ok(2) => {
+            let (default_scale, default_shape) = match default {
+                generator::Distribution::Pareto { scale, shape } => (scale, shape),
+                _ => (10f64, 1.5),
+            };
+            generator::Distribution::Pareto { scale: input_with_default(format!("{} scale (the smallest value)", name), default_scale), shape: input_with_default(format!("{} shape (smaller - heavier tail)", name), default_shape) }
+        },
+        Ok(3) => {
+            let (default_short_mean, default_long_mean, default_long_probability) = match default {
+                generator::Distribution::Bimodal { short_mean, long_mean, long_probability } => (short_mean, long_mean, long_probability),
+                _ => (10f64, 200f64, 0.2),
+            };
+            generator::Distribution::Bimodal { short_mean: input_with_default(format!("{} short mean", name), default_short_mean), long_mean: input_with_default(format!("{} long mean", name), default_long_mean), long_probability: input_with_default(format!("{} long probability [0;1]", name), default_long_probability) }
+        },
+        _ => {
+            let (default_minimum, default_maximum) = match default {
+                generator::Distribution::Uniform { minimum, maximum } => (minimum, maximum),
+                _ => (minimum_value, 100),
+            };
+            let minimum = input_with_default(format!("Minimum {}", name.to_lowercase()), default_minimum).max(minimum_value);
+            generator::Distribution::Uniform { minimum, maximum: input_with_default(format!("Maximum {}", name.to_lowercase()), default_maximum).max(minimum) }
+        },
+    };
+    match distribution.is_valid() {
+        true => distribution,
+        false => {
+            println!("Invalid parameters, default {} distribution is used", name.to_lowercase());
+            default
+        },
+    }
+}
+
+fn generator_settings_menu() -> generator::GeneratorSettings {
+    let mut generator_settings = generator::GeneratorSettings::default();
+    generator_settings.minimum_processes_count = input_with_default("Minimum number of processes in test", generator_settings.minimum_processes_count);
+    generator_settings.maximum_processes_count = input_with_default("Maximum number of processes in test", generator_settings.maximum_processes_count).max(generator_settings.minimum_processes_count);
+    generator_settings.inter_arrival_time = distribution_menu("Time between new processes (exponential - Poisson arrivals)", generator_settings.inter_arrival_time, true);
+    let job_classes_count: usize = input_with_default("Job classes count (e.g. 2 - mix of short and long jobs)", 1).max(1);
+    generator_settings.job_classes = (0..job_classes_count).map(|class| generator::JobClass {
+        share: match job_classes_count {
+            1 => 1f64,
+            _ => input_with_default(format!("Share of job class {}", class), 1f64).max(0f64),
+        },
+        duration: distribution_menu(&format!("Process duration of job class {}", class), generator::Distribution::Uniform { minimum: 1, maximum: 300 }, false),
+    }).collect();
+    if generator_settings.job_classes.iter().all(|class| class.share == 0f64) {
+        generator_settings.job_classes[0].share = 1f64;
+    }
+    generator_settings.lifetime_probability = input_with_default::<u32, _>("Life time probability per mil [0;1000]", 300).min(1000) as f64 / 1000f64;
+    generator_settings.lifetime = distribution_menu("Lifetime", generator_settings.lifetime, true);
+    generator_settings.priority_classes_count = input_with_default("Priority classes count", generator_settings.priority_classes_count).max(1);
+    generator_settings.minimum_tickets = input_with_default("Minimum tickets count (cannot be 0, it will be incremented if so)", generator_settings.minimum_tickets).max(1);
+    generator_settings.maximum_tickets = input_with_default("Maximum tickets count", generator_settings.maximum_tickets).max(generator_settings.minimum_tickets);
+    generator_settings.maximum_io_bursts_count = input_with_default("Maximum I/O bursts count per process", generator_settings.maximum_io_bursts_count);
+    if generator_settings.maximum_io_bursts_count != 0 {
+        generator_settings.io_duration = distribution_menu("I/O burst duration", generator_settings.io_duration, false);
+    }
+    generator_settings.groups_count = input_with_default("Groups count (owners of processes)", generator_settings.groups_count).max(1);
+    generator_settings.seed = input_with_default::<String, _>("Seed (empty - random)", String::new()).trim().parse().ok();
+    generator_settings
+}
+
+fn random_workload_menu(settings: &simulator::SimulationSettings) -> Option<simulator::Processes> {
+    let processes = match generator::generate(&generator_settings_menu()) {
+        Ok(processes) => processes,
+        Err(err) => {
+            println!("Cannot generate workload: {}", err);
+            return None;
+        },
+    };
+    let workload_output: String = input_with_default("Save generated workload to file (empty - don't save)", settings.workload_output.clone().unwrap_or_default());
+    if !workload_output.trim().is_empty() {
+        match workload::save(&processes, workload_output.trim()) {
+            Ok(()) => println!("Workload saved to {}", workload_output.trim()),
+            Err(err) => println!("Cannot save workload: {}", err),
         }
-        processes_list
-    }), quant));
+    }
+    Some(processes)
+}
+
+#[inline]
+fn random_test_menu(settings: &simulator::SimulationSettings) {
+    if let Some(processes) = random_workload_menu(settings) {
+        run_simulations(processes, settings);
+    }
 }
 
-fn manual_test(quant: u32) {
+fn manual_test(settings: &simulator::SimulationSettings) {
     let mut processes = Vec::with_capacity(input("Processes count"));
     for i in 1..=processes.capacity() {
         println!("Process {}.", i);
-        processes.push((input("Time to start after previous process"), input("Process duration"), match dialoguer::Select::new().with_prompt("With probability").items(&["No", "Yes"]).interact() {
+        let wait = input("Time to start after previous process");
+        let description = cpu_access_manager::ProcessDescription::new(input("Process duration"), match dialoguer::Select::new().with_prompt("With probability").items(&["No", "Yes"]).interact() {
             Ok(1) => Some(input("Lifetime")),
             _ => None,
-        }));
+        }, input_with_default("Priority (0 - the most important)", 0));
+        let io_bursts = (0..input_with_default("I/O bursts count", 0)).map(|_| (input("I/O burst duration"), input("Next processor burst duration"))).collect();
+        let tickets = input_with_default("Tickets count", cpu_access_manager::DEFAULT_TICKETS).max(1);
+        processes.push((wait, description.with_io_bursts(io_bursts).with_tickets(tickets)));
+    }
+    run_simulations(simulator::Processes::from(processes), settings);
+}
+
+fn replay_menu(settings: &simulator::SimulationSettings) {
+    let path: String = input("Workload file (.csv or .json)");
+    match workload::load(path.trim()) {
+        Ok(processes) => run_simulations(processes, settings),
+        Err(err) => println!("Cannot load workload: {}", err),
+    }
+}
+
+fn quantum_sweep_menu(settings: &simulator::SimulationSettings) {
+    let processes = match dialoguer::Select::new().with_prompt("Workload").items(&["Random workload", "Workload file"]).interact() {
+        Ok(1) => match workload::load(input::<String, _>("Workload file (.csv or .json)").trim()) {
+            Ok(processes) => processes,
+            Err(err) => {
+                println!("Cannot load workload: {}", err);
+                return;
+            },
+        },
+        _ => match random_workload_menu(settings) {
+            Some(processes) => processes,
+            None => return,
+        },
+    };
+    let minimum_quantum: cpu_access_manager::Time = input_with_default("Minimum quant", 1).max(1);
+    let maximum_quantum: cpu_access_manager::Time = input_with_default("Maximum quant", 50).max(minimum_quantum);
+    let step: usize = input_with_default("Quant step", 1).max(1);
+    let format = table_format_menu();
+    let output: String = input_with_default("Output file (empty - print)", String::new());
+    match simulator::sweep_quantum(processes, settings, (minimum_quantum..=maximum_quantum).step_by(step)) {
+        Ok(results) => save_table(report::to_table("quant", &results, format), &output),
+        Err(err) => println!("Quantum sweep failed: {}", err),
+    }
+}
+
+fn experiment_menu(settings: &simulator::SimulationSettings) {
+    let generator_settings = generator_settings_menu();
+    let runs_count: usize = input_with_default("Runs count", 30).max(1);
+    let format = table_format_menu();
+    let results = match experiment::run_experiment(&generator_settings, settings, runs_count) {
+        Ok(results) => results,
+        Err(err) => {
+            println!("Experiment failed: {}", err);
+            return;
+        },
+    };
+    println!("Base seed: {}, runs: {}", results.get_base_seed(), results.get_runs_count());
+    save_table(results.to_summary_table(format), &input_with_default::<String, _>("Summary output file (empty - print)", String::new()));
+    let metric = dialoguer::Select::new().with_prompt("Metric of pairwise comparison").items(results.get_metrics()).default(1).interact().unwrap_or(1);
+    save_table(results.to_pairwise_table(metric, format), &input_with_default::<String, _>("Pairwise comparison output file (empty - print)", String::new()));
+}
+
+fn visualizer_menu(settings: &simulator::SimulationSettings) {
+    let registry = Registry::new();
+    let names = registry.get_names();
+    let name = names[dialoguer::Select::new().with_prompt("Algorithm").items(&names).default(0).interact().unwrap_or(0)];
+    let processes = match dialoguer::Select::new().with_prompt("Workload").items(&["Random workload", "Workload file"]).interact() {
+        Ok(1) => match workload::load(input::<String, _>("Workload file (.csv or .json)").trim()) {
+            Ok(processes) => processes,
+            Err(err) => {
+                println!("Cannot load workload: {}", err);
+                return;
+            },
+        },
+        _ => match random_workload_menu(settings) {
+            Some(processes) => processes,
+            None => return,
+        },
+    };
+    let stepper = match registry.create(name, settings).map(|cpu| cpu.map(|cpu| visualizer::Stepper::new(cpu, processes))) {
+        Ok(Some(Ok(stepper))) => stepper,
+        Ok(Some(Err(err))) | Err(err) => {
+            println!("Cannot start simulation: {}", err);
+            return;
+        },
+        Ok(None) => return,
+    };
+    if let Err(err) = visualizer::run(name, stepper) {
+        println!("Terminal user interface failed: {}", err);
     }
-    print_results(simulator::simulate_every(simulator::Processes::from(processes), quant));
 }
 
-fn main() {
-    let mut quant = 5;
+fn periodic_analysis_menu() {
+    let tasks: Vec<periodic::PeriodicTask> = (0..input::<usize, _>("Tasks count")).map(|task| {
+        let period = input(format!("Period of task {}", task));
+        let wcet = input(format!("WCET of task {}", task));
+        periodic::PeriodicTask::new(period, wcet, input_with_default(format!("Relative deadline of task {}", task), period))
+    }).collect();
+    let assignment = match dialoguer::Select::new().with_prompt("Priority assignment").items(&["Rate monotonic", "Deadline monotonic"]).interact() {
+        Ok(1) => periodic::PriorityAssignment::DeadlineMonotonic,
+        _ => periodic::PriorityAssignment::RateMonotonic,
+    };
+    let hyperperiod = match periodic::get_hyperperiod(&tasks) {
+        Ok(hyperperiod) => hyperperiod,
+        Err(err) => {
+            println!("Cannot compute hyperperiod: {}", err);
+            return;
+        },
+    };
+    let horizon = input_with_default("Simulation horizon (default - hyperperiod)", hyperperiod).max(1);
+    let report = match schedulability::analyze(&tasks, assignment, horizon) {
+        Ok(report) => report,
+        Err(err) => {
+            println!("Analysis failed: {}", err);
+            return;
+        },
+    };
+    print_schedulability_summary(&report);
+    let format = table_format_menu();
+    save_table(report.to_table(format), &input_with_default::<String, _>("Output file (empty - print)", String::new()));
+}
+
+fn print_schedulability_summary(report: &schedulability::SchedulabilityReport) {
+    println!("Utilization: {:.4}, Liu & Layland bound: {:.4}", report.get_utilization(), report.get_liu_layland_bound());
+    println!("{}", match report.is_schedulable_by_utilization() {
+        Some(true) => "Utilization bound test: schedulable",
+        Some(false) => "Utilization bound test: inconclusive",
+        None => "Utilization bound test doesn't apply, some deadlines are shorter than periods",
+    });
+    println!("Response time analysis: {}", match report.is_schedulable_by_response_time() {
+        true => "schedulable",
+        false => "not schedulable",
+    });
+    println!("Simulation of {} ticks {} the analysis", report.get_horizon(), match report.is_prediction_confirmed() {
+        true => "confirms",
+        false => "doesn't confirm",
+    });
+}
+
+fn table_format_menu() -> report::TableFormat {
+    match dialoguer::Select::new().with_prompt("Table format").items(&["CSV", "Markdown"]).interact() {
+        Ok(1) => report::TableFormat::Markdown,
+        _ => report::TableFormat::Csv,
+    }
+}
+
+fn save_table(table: String, output: &str) {
+    match output.trim() {
+        "" => print!("{}", table),
+        path => match std::fs::write(path, table) {
+            Ok(()) => println!("Table saved to {}", path),
+            Err(err) => println!("Cannot save table: {}", err),
+        },
+    }
+}
+
+fn fair_share_settings_menu(settings: &mut simulator::SimulationSettings) {
+    let groups_count: usize = input_with_default("Groups with configured share count (the other groups get share 1)", settings.group_shares.len());
+    settings.group_shares = (0..groups_count).map(|group| input_with_default(format!("Share of group {}", group), settings.group_shares.get(group).copied().unwrap_or(1)).max(1)).collect();
+    let policies = [fair_share::GroupPolicy::RoundRobin, fair_share::GroupPolicy::FCFS, fair_share::GroupPolicy::SJF, fair_share::GroupPolicy::Priority];
+    let default_index = policies.iter().position(|policy| *policy == settings.group_policy).unwrap_or(0);
+    settings.group_policy = policies[dialoguer::Select::new().with_prompt("Policy inside group").items(&["Round robin", "FCFS", "SJF", "Priority"]).default(default_index).interact().unwrap_or(default_index)];
+}
+
+fn mlfq_settings_menu(settings: &mut simulator::SimulationSettings) {
+    let levels_count: usize = input_with_default("MLFQ levels count", settings.mlfq_quantum_times.len()).max(1);
+    settings.mlfq_quantum_times = (0..levels_count).map(|level| input_with_default(format!("Quant time of level {}", level), settings.mlfq_quantum_times.get(level).copied().unwrap_or(settings.quant << level)).max(1)).collect();
+    settings.mlfq_boost_period = match input_with_default("Priority boost period (0 - no boost)", settings.mlfq_boost_period.unwrap_or(0)) {
+        0 => None,
+        period => Some(period),
+    };
+}
+
+fn main() -> std::process::ExitCode {
+    let cli = cli::Cli::parse();
+    let mut settings = simulator::SimulationSettings::default();
+    if let Err(err) = cli.simulation.apply(&mut settings) {
+        eprintln!("Error: {}", err);
+        return std::process::ExitCode::FAILURE;
+    }
+    if let Some(command) = cli.command.or(cli.replay.map(|path| cli::Command::File { path })) {
+        return match cli::run(command, &settings, &cli.output) {
+            Ok(()) => std::process::ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                std::process::ExitCode::FAILURE
+            },
+        };
+    }
     loop {
         match dialoguer::Select::new()
-            .items(&["Random tests", "Manual test", "Change quant", "Exit"])
+            .items(&["Random tests", "Manual test", "Replay workload file", "Quantum sweep", "Multi-run experiment", "Periodic task set analysis", "Step-through visualizer", "Change quant", "Change MLFQ parameters", "Change priority aging", "Change context switch cost", "Change trace export", "Change multiprocessor parameters", "Change burst prediction parameters", "Change CFS parameters", "Change lottery seed", "Change deadline scheduling", "Change fair share groups", "Change adaptive quant", "Exit"])
             .with_prompt("Select option")
             .interact() {
-            Ok(0) => random_test_menu(quant),
-            Ok(1) => manual_test(quant),
-            Ok(2) => quant = input_with_default("Quant time", quant).max(1),
-            Ok(3) => break,
+            Ok(0) => random_test_menu(&settings),
+            Ok(1) => manual_test(&settings),
+            Ok(2) => replay_menu(&settings),
+            Ok(3) => quantum_sweep_menu(&settings),
+            Ok(4) => experiment_menu(&settings),
+            Ok(5) => periodic_analysis_menu(),
+            Ok(6) => visualizer_menu(&settings),
+            Ok(7) => settings.quant = input_with_default("Quant time", settings.quant).max(1),
+            Ok(8) => mlfq_settings_menu(&mut settings),
+            Ok(9) => settings.aging_period = match input_with_default("Aging period (0 - no aging)", settings.aging_period.unwrap_or(0)) {
+                0 => None,
+                period => Some(period),
+            },
+            Ok(10) => settings.context_switch_cost = input_with_default("Context switch cost", settings.context_switch_cost),
+            Ok(11) => settings.trace_directory = match input_with_default::<String, _>("Trace export directory (empty - no trace)", settings.trace_directory.clone().unwrap_or_default()) {
+                directory if directory.trim().is_empty() => None,
+                directory => Some(directory),
+            },
+            Ok(12) => {
+                settings.cores_count = input_with_default("Cores count", settings.cores_count).max(1);
+                settings.load_balance_period = input_with_default("Load balance period of per-core queues", settings.load_balance_period).max(1);
+            },
+            Ok(13) => {
+                settings.prediction_alpha = input_with_default("Prediction alpha [0;1]", settings.prediction_alpha).clamp(0f64, 1f64);
+                settings.initial_burst_estimate = input_with_default("Initial burst estimate", settings.initial_burst_estimate).max(0f64);
+            },
+            Ok(14) => {
+                settings.cfs_target_latency = input_with_default("CFS target latency", settings.cfs_target_latency).max(1);
+                settings.cfs_minimum_granularity = input_with_default("CFS minimum granularity", settings.cfs_minimum_granularity).max(1);
+            },
+            Ok(15) => settings.lottery_seed = input_with_default::<String, _>("Lottery seed (empty - random)", settings.lottery_seed.map(|seed| seed.to_string()).unwrap_or_default()).trim().parse().ok(),
+            Ok(16) => settings.abandon_hopeless_processes = matches!(dialoguer::Select::new().with_prompt("Abandon processes which can't meet their deadlines").items(&["No", "Yes"]).default(settings.abandon_hopeless_processes as usize).interact(), Ok(1)),
+            Ok(17) => fair_share_settings_menu(&mut settings),
+            Ok(18) => {
+                settings.adaptive_minimum_quant = input_with_default("Minimum adaptive quant", settings.adaptive_minimum_quant).max(1);
+                settings.adaptive_maximum_quant = input_with_default("Maximum adaptive quant", settings.adaptive_maximum_quant).max(settings.adaptive_minimum_quant);
+                settings.adaptive_target_latency = input_with_default("Target latency of adaptive quant", settings.adaptive_target_latency).max(1);
+            },
+            Ok(19) => break,
             Ok(_) | Err(_) => (),
         }
     }
@@ -114,4 +495,5 @@ fn main() {
     // for processes_list in processes_lists {
     //     simulator::simulate_every(processes_list);
     // }
+    std::process::ExitCode::SUCCESS
 }
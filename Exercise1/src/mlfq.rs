@@ -0,0 +1,244 @@
+use std::collections::VecDeque;
+
+use crate::cpu_access_manager::{self, CpuAccessManager, Process, ProcessDescription, SimulationError, Time};
+use crate::io_device::IoDevice;
+use crate::trace::Trace;
+
+// Every queue keeps processes together with the part of quantum that is still left for them on their level
+#[derive(Debug, Clone)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct MLFQ {
+    levels: Vec<VecDeque<(Process, Time)>>,
+    quantum_times: Vec<Time>,
+    boost_period: Option<Time>, // Counted in simulated time, including idle time and context switches
+    time_to_boost: Time,
+    is_ending: bool,
+    working_time: Time,
+    waiting_time: Time,
+    total_working_time: Time,
+    total_waiting_time: Time,
+    next_id: u32,
+    current_time: Time,
+    statistics: Vec<cpu_access_manager::ProcessStatistics>,
+    total_required_time: Time,
+    current_id: Option<u32>,
+    context_switch: cpu_access_manager::ContextSwitch,
+    trace: Trace,
+    io_device: IoDevice<(Process, usize)>,
+}
+
+impl MLFQ {
+    pub fn new(quantum_times: Vec<Time>, boost_period: Option<Time>, context_switch_cost: Time) -> Result<Self, SimulationError> {
+        if quantum_times.is_empty() {
+            return Err(SimulationError::InvalidParameter("MLFQ needs at least one level"));
+        }
+        if quantum_times.contains(&0) {
+            return Err(SimulationError::InvalidParameter("Quant cannot be 0"));
+        }
+        if boost_period == Some(0) {
+            return Err(SimulationError::InvalidParameter("Boost period cannot be 0"));
+        }
+        Ok(Self { levels: vec![VecDeque::new(); quantum_times.len()], quantum_times, boost_period, time_to_boost: boost_period.unwrap_or(0), is_ending: false, working_time: 0, waiting_time: 0, total_working_time: 0, total_waiting_time: 0, next_id: 0, current_time: 0, statistics: Vec::new(), total_required_time: 0, current_id: None, context_switch: cpu_access_manager::ContextSwitch::new(context_switch_cost), trace: Trace::new(), io_device: IoDevice::new() })
+    }
+
+    fn boost(&mut self) {
+        let quantum_time = self.quantum_times[0];
+        for level in 1..self.levels.len() {
+            while let Some((process, _)) = self.levels[level].pop_front() {
+                self.levels[0].push_back((process, quantum_time));
+            }
+        }
+        for (_, quantum_left) in self.levels[0].iter_mut() {
+            *quantum_left = quantum_time;
+        }
+        self.time_to_boost = self.boost_period.unwrap();
+    }
+
+    // Process which gave up processor before end of its quantum stays on its level
+    fn return_from_io(&mut self) {
+        for ((mut process, level), time) in self.io_device.advance_to(self.current_time) {
+            process.end_io(time);
+            self.trace.io_completion(time, process.get_id());
+            self.levels[level].push_back((process, self.quantum_times[level]));
+        }
+    }
+
+    // Single period of work, context switch or idleness, no longer than n
+    fn simulate_step(&mut self, mut n: Time) {
+        if let Some(level) = self.levels.iter().position(|level| !level.is_empty()) {
+            let (mut current, quantum_left) = self.levels[level].pop_front().unwrap();
+            let switch_time = self.context_switch.switch_to(current.get_id(), self.io_device.limit(self.current_time, n));
+            n -= switch_time;
+            self.current_time += switch_time;
+            if self.context_switch.is_switching() {
+                self.levels[level].push_front((current, quantum_left));
+                return;
+            }
+            let time_for_process = self.io_device.limit(self.current_time, n).min(quantum_left);
+            let is_new_call = self.current_id != Some(current.get_id());
+            let worked_time = current.work_for(self.current_time, time_for_process, is_new_call);
+            if worked_time != 0 {
+                self.current_id = Some(current.get_id());
+            }
+            self.trace.run(current.get_id(), self.current_time, worked_time);
+            self.current_time += worked_time;
+            self.total_required_time -= worked_time;
+            self.total_working_time += worked_time;
+            if current.is_finished() {
+                self.trace.finish(self.current_time, &current);
+                self.total_required_time -= current.get_total_time_left();
+                self.statistics.push(current.finalize());
+            }
+            else if current.is_blocked() {
+                let io_time = current.start_io();
+                self.trace.block(self.current_time, &current);
+                self.io_device.block(self.current_time, (current, level), io_time);
+                self.current_id = None;
+            }
+            else if worked_time == quantum_left {
+                let next_level = (level + 1).min(self.levels.len() - 1);
+                self.levels[next_level].push_back((current, self.quantum_times[next_level]));
+            }
+            else {
+                self.levels[level].push_front((current, quantum_left - worked_time));
+            }
+        }
+        else {
+            let idle_time = self.io_device.limit(self.current_time, n);
+            self.waiting_time = idle_time;
+            self.working_time = 0;
+            self.total_waiting_time += self.waiting_time;
+            self.current_time += idle_time;
+        }
+    }
+}
+
+impl CpuAccessManager for MLFQ {
+    fn add_process(&mut self, description: &ProcessDescription) -> Result<u32, SimulationError> {
+        description.validate()?;
+        let total_required_time = self.total_required_time.checked_add(description.get_total_duration()).ok_or(SimulationError::TimeOverflow)?;
+        self.levels[0].push_back((Process::new(self.next_id, self.current_time, description), self.quantum_times[0]));
+        let ans = self.next_id;
+        self.trace.arrival(self.current_time, ans);
+        self.next_id += 1;
+        self.total_required_time = total_required_time;
+        Ok(ans)
+    }
+
+    #[inline]
+    fn is_working(&self) -> bool {
+        self.levels.iter().any(|level| !level.is_empty()) || self.io_device.is_working()
+    }
+
+    #[inline]
+    fn simulate_one_tick(&mut self) {
+        self.simulate_n_ticks(1)
+    }
+
+    fn simulate_n_ticks(&mut self, mut n: Time) {
+        while n != 0 {
+            self.return_from_io();
+            if self.is_ending {
+                n = n.min(self.total_required_time);
+            }
+            let start_time = self.current_time;
+            match self.boost_period {
+                Some(_) => self.simulate_step(n.min(self.time_to_boost)),
+                None => self.simulate_step(n),
+            }
+            let elapsed_time = self.current_time - start_time;
+            n -= elapsed_time;
+            if self.boost_period.is_some() {
+                self.time_to_boost -= elapsed_time;
+                if self.time_to_boost == 0 {
+                    self.boost();
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn get_total_working_time(&self) -> Time {
+        self.total_working_time
+    }
+
+    #[inline]
+    fn get_total_waiting_time(&self) -> Time {
+        self.total_waiting_time
+    }
+
+    #[inline]
+    fn get_working_time(&self) -> Time {
+        self.working_time
+    }
+
+    #[inline]
+    fn get_waiting_time(&self) -> Time {
+        self.waiting_time
+    }
+
+    #[inline]
+    fn get_context_switches_count(&self) -> usize {
+        self.context_switch.get_count()
+    }
+
+    #[inline]
+    fn get_context_switches_time(&self) -> Time {
+        self.context_switch.get_total_time()
+    }
+
+    #[inline]
+    fn get_io_utilization(&self) -> f64 {
+        self.io_device.get_working_time() as f64 / self.current_time.max(1) as f64
+    }
+
+    #[inline]
+    fn enable_trace(&mut self) {
+        self.trace.enable()
+    }
+
+    #[inline]
+    fn get_trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    #[inline]
+    fn simulate_till_end_of_every_process(&mut self) {
+        self.is_ending = true;
+        while self.is_working() {
+            self.simulate_n_ticks(self.total_required_time)
+        }
+        self.is_ending = false;
+    }
+
+    #[inline]
+    fn finalize(mut self: Box<Self>) -> Vec<cpu_access_manager::ProcessStatistics> {
+        self.simulate_till_end_of_every_process();
+        self.statistics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Boost period is counted from the start of simulation, idle time before arrival brings the boost closer too
+    #[test]
+    fn cpu_bound_process_is_demoted_and_boosted() {
+        let mut cpu = MLFQ::new(vec![2, 4, 8], Some(20), 0).unwrap();
+        let get_level = |cpu: &MLFQ| cpu.levels.iter().position(|level| !level.is_empty());
+        cpu.simulate_n_ticks(8);
+        cpu.add_process(&ProcessDescription::new(100, None, 0)).unwrap();
+        cpu.simulate_n_ticks(2);
+        assert_eq!(get_level(&cpu), Some(1));
+        cpu.simulate_n_ticks(4);
+        assert_eq!(get_level(&cpu), Some(2));
+        cpu.simulate_n_ticks(5);
+        assert_eq!(get_level(&cpu), Some(2));
+        cpu.simulate_n_ticks(1);
+        assert_eq!(get_level(&cpu), Some(0));
+        assert_eq!(cpu.levels[0].front().map(|(_, quantum_left)| *quantum_left), Some(2));
+        cpu.simulate_n_ticks(2);
+        assert_eq!(get_level(&cpu), Some(1));
+    }
+}
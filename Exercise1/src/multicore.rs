@@ -0,0 +1,328 @@
+use std::collections::VecDeque;
+
+use crate::cpu_access_manager::{self, ContextSwitch, CpuAccessManager, Process, ProcessDescription, SimulationError, Time};
+use crate::io_device::IoDevice;
+use crate::trace::Trace;
+
+#[derive(Debug, Clone, Copy)]
+pub enum ReadyQueue {
+    Global,
+    PerCore { balance_period: Time },
+}
+
+// Processes in ready queues remember core on which they were running last time, so migrations can be counted
+#[derive(Debug, Clone)]
+struct Core {
+    current_process: Option<Process>,
+    queue: VecDeque<(Process, Option<usize>)>,
+    quantum_left: Time,
+    is_new_call: bool,
+    working_time: Time,
+    context_switch: ContextSwitch,
+}
+
+// Every core works as round robin, processes are taken either from one global queue or from core's own queue
+#[derive(Debug, Clone)]
+pub struct MultiCore {
+    cores: Vec<Core>,
+    ready_queue: ReadyQueue,
+    global_queue: VecDeque<(Process, Option<usize>)>,
+    quantum_time: Time,
+    time_to_balance: Time,
+    migrations_count: usize,
+    is_ending: bool,
+    working_time: Time,
+    waiting_time: Time,
+    total_working_time: Time,
+    total_waiting_time: Time,
+    next_id: u32,
+    current_time: Time,
+    statistics: Vec<cpu_access_manager::ProcessStatistics>,
+    total_required_time: Time,
+    trace: Trace,
+    io_device: IoDevice<(Process, usize)>,
+}
+
+impl MultiCore {
+    pub fn new(cores_count: usize, ready_queue: ReadyQueue, quantum_time: Time, context_switch_cost: Time) -> Result<Self, SimulationError> {
+        if cores_count == 0 {
+            return Err(SimulationError::InvalidParameter("There has to be at least one core"));
+        }
+        if quantum_time == 0 {
+            return Err(SimulationError::InvalidParameter("Quant cannot be 0"));
+        }
+        let time_to_balance = match ready_queue {
+            ReadyQueue::PerCore { balance_period: 0 } => return Err(SimulationError::InvalidParameter("Balance period cannot be 0")),
+            ReadyQueue::PerCore { balance_period } => balance_period,
+            ReadyQueue::Global => 0,
+        };
+        let core = Core { current_process: None, queue: VecDeque::new(), quantum_left: quantum_time, is_new_call: false, working_time: 0, context_switch: ContextSwitch::new(context_switch_cost) };
+        Ok(Self { cores: vec![core; cores_count], ready_queue, global_queue: VecDeque::new(), quantum_time, time_to_balance, migrations_count: 0, is_ending: false, working_time: 0, waiting_time: 0, total_working_time: 0, total_waiting_time: 0, next_id: 0, current_time: 0, statistics: Vec::new(), total_required_time: 0, trace: Trace::new(), io_device: IoDevice::new() })
+    }
+
+    fn dispatch(&mut self) {
+        for index in 0..self.cores.len() {
+            if self.cores[index].current_process.is_some() {
+                continue;
+            }
+            let next = match self.ready_queue {
+                ReadyQueue::Global => self.global_queue.pop_front(),
+                ReadyQueue::PerCore { .. } => self.cores[index].queue.pop_front(),
+            };
+            if let Some((process, last_core)) = next {
+                if last_core.is_some_and(|last_core| last_core != index) {
+                    self.migrations_count += 1;
+                }
+                let core = &mut self.cores[index];
+                core.current_process = Some(process);
+                core.quantum_left = self.quantum_time;
+                core.is_new_call = true;
+            }
+        }
+    }
+
+    // Process which reached its lifetime can't work anymore, so it leaves the core before the step instead of wasting it
+    fn expire_processes(&mut self) -> bool {
+        let mut any_expired = false;
+        for core in self.cores.iter_mut() {
+            if let Some(mut current) = core.current_process.take_if(|current| current.get_deadline().is_some_and(|deadline| deadline <= self.current_time)) {
+                current.abandon(self.current_time);
+                self.trace.finish(self.current_time, &current);
+                self.total_required_time -= current.get_total_time_left();
+                self.statistics.push(current.finalize());
+                any_expired = true;
+            }
+        }
+        any_expired
+    }
+
+    // Process goes back to the queue of the core it was running on
+    fn return_from_io(&mut self) {
+        for ((mut process, core), time) in self.io_device.advance_to(self.current_time) {
+            process.end_io(time);
+            self.trace.io_completion(time, process.get_id());
+            match self.ready_queue {
+                ReadyQueue::Global => self.global_queue.push_back((process, Some(core))),
+                ReadyQueue::PerCore { .. } => self.cores[core].queue.push_back((process, Some(core))),
+            }
+        }
+    }
+
+    // Moves waiting processes from the longest queues to the shortest ones until their lengths differ by at most one
+    fn balance(&mut self) {
+        loop {
+            let longest = (0..self.cores.len()).max_by_key(|index| self.cores[*index].queue.len()).unwrap();
+            let shortest = (0..self.cores.len()).min_by_key(|index| self.cores[*index].queue.len()).unwrap();
+            if self.cores[longest].queue.len() <= self.cores[shortest].queue.len() + 1 {
+                return;
+            }
+            let entry = self.cores[longest].queue.pop_back().unwrap();
+            self.cores[shortest].queue.push_back(entry);
+        }
+    }
+}
+
+impl CpuAccessManager for MultiCore {
+    fn add_process(&mut self, description: &ProcessDescription) -> Result<u32, SimulationError> {
+        description.validate()?;
+        let total_required_time = self.total_required_time.checked_add(description.get_total_duration()).ok_or(SimulationError::TimeOverflow)?;
+        let process = Process::new(self.next_id, self.current_time, description);
+        match self.ready_queue {
+            ReadyQueue::Global => self.global_queue.push_back((process, None)),
+            ReadyQueue::PerCore { .. } => {
+                let core = self.cores.iter_mut().min_by_key(|core| core.queue.len() + core.current_process.is_some() as usize).unwrap();
+                core.queue.push_back((process, None));
+            },
+        }
+        let ans = self.next_id;
+        self.trace.arrival(self.current_time, ans);
+        self.next_id += 1;
+        self.total_required_time = total_required_time;
+        Ok(ans)
+    }
+
+    #[inline]
+    fn is_working(&self) -> bool {
+        !self.global_queue.is_empty() || self.cores.iter().any(|core| core.current_process.is_some() || !core.queue.is_empty()) || self.io_device.is_working()
+    }
+
+    #[inline]
+    fn simulate_one_tick(&mut self) {
+        self.simulate_n_ticks(1)
+    }
+
+    fn simulate_n_ticks(&mut self, mut n: Time) {
+        while n != 0 {
+            self.return_from_io();
+            self.dispatch();
+            while self.expire_processes() {
+                self.dispatch();
+            }
+            if self.is_ending {
+                n = n.min(self.total_required_time.div_ceil(self.cores.len() as Time));
+            }
+            if self.cores.iter().all(|core| core.current_process.is_none()) {
+                let idle_time = self.io_device.limit(self.current_time, n);
+                self.waiting_time = idle_time;
+                self.working_time = 0;
+                self.total_waiting_time += self.waiting_time;
+                self.current_time += idle_time;
+                n -= idle_time;
+                continue;
+            }
+            let mut step = self.cores.iter().filter_map(|core| core.current_process.as_ref().map(|current| current.get_time_left().min(core.quantum_left).min(current.get_deadline().map_or(Time::MAX, |deadline| deadline - self.current_time)))).fold(self.io_device.limit(self.current_time, n), Time::min);
+            if let ReadyQueue::PerCore { .. } = self.ready_queue {
+                step = step.min(self.time_to_balance);
+            }
+            for (index, core) in self.cores.iter_mut().enumerate() {
+                if let Some(mut current) = core.current_process.take() {
+                    let switch_time = core.context_switch.switch_to(current.get_id(), step);
+                    let worked_time = current.work_for(self.current_time + switch_time, step - switch_time, core.is_new_call);
+                    if worked_time != 0 {
+                        core.is_new_call = false;
+                    }
+                    core.quantum_left -= worked_time;
+                    core.working_time += worked_time;
+                    self.total_required_time -= worked_time;
+                    self.total_working_time += worked_time;
+                    self.trace.run_on_core(index, current.get_id(), self.current_time + switch_time, worked_time);
+                    if current.is_finished() {
+                        self.trace.finish(self.current_time + switch_time + worked_time, &current);
+                        self.total_required_time -= current.get_total_time_left();
+                        self.statistics.push(current.finalize());
+                    }
+                    else if current.is_blocked() {
+                        let io_time = current.start_io();
+                        self.trace.block(self.current_time + switch_time + worked_time, &current);
+                        self.io_device.block(self.current_time + switch_time + worked_time, (current, index), io_time);
+                    }
+                    else if core.quantum_left == 0 {
+                        match self.ready_queue {
+                            ReadyQueue::Global => self.global_queue.push_back((current, Some(index))),
+                            ReadyQueue::PerCore { .. } => core.queue.push_back((current, Some(index))),
+                        }
+                    }
+                    else {
+                        core.current_process = Some(current);
+                    }
+                }
+            }
+            n -= step;
+            self.current_time += step;
+            if let ReadyQueue::PerCore { balance_period } = self.ready_queue {
+                self.time_to_balance -= step;
+                if self.time_to_balance == 0 {
+                    self.balance();
+                    self.time_to_balance = balance_period;
+                }
+            }
+        }
+    }
+
+    #[inline]
+    fn get_total_working_time(&self) -> Time {
+        self.total_working_time
+    }
+
+    #[inline]
+    fn get_total_waiting_time(&self) -> Time {
+        self.total_waiting_time
+    }
+
+    #[inline]
+    fn get_working_time(&self) -> Time {
+        self.working_time
+    }
+
+    #[inline]
+    fn get_waiting_time(&self) -> Time {
+        self.waiting_time
+    }
+
+    #[inline]
+    fn get_context_switches_count(&self) -> usize {
+        self.cores.iter().map(|core| core.context_switch.get_count()).sum()
+    }
+
+    #[inline]
+    fn get_context_switches_time(&self) -> Time {
+        self.cores.iter().map(|core| core.context_switch.get_total_time()).sum()
+    }
+
+    #[inline]
+    fn get_cores_utilization(&self) -> Vec<f64> {
+        self.cores.iter().map(|core| core.working_time as f64 / self.current_time.max(1) as f64).collect()
+    }
+
+    #[inline]
+    fn get_migrations_count(&self) -> usize {
+        self.migrations_count
+    }
+
+    #[inline]
+    fn get_io_utilization(&self) -> f64 {
+        self.io_device.get_working_time() as f64 / self.current_time.max(1) as f64
+    }
+
+    #[inline]
+    fn enable_trace(&mut self) {
+        self.trace.enable()
+    }
+
+    #[inline]
+    fn get_trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    // Remaining work is done by all cores together, so every call is limited to the part of a single core
+    #[inline]
+    fn simulate_till_end_of_every_process(&mut self) {
+        self.is_ending = true;
+        while self.is_working() {
+            self.simulate_n_ticks(self.total_required_time.div_ceil(self.cores.len() as Time))
+        }
+        self.is_ending = false;
+    }
+
+    #[inline]
+    fn finalize(mut self: Box<Self>) -> Vec<cpu_access_manager::ProcessStatistics> {
+        self.simulate_till_end_of_every_process();
+        self.statistics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Long processes are placed on core 0 and short ones on core 1, so core 1 runs out of work and only balancing can give it some
+    fn simulate(balance_period: Time) -> MultiCore {
+        let mut cpu = MultiCore::new(2, ReadyQueue::PerCore { balance_period }, 5, 0).unwrap();
+        for duration in [100, 2, 100, 2, 100] {
+            cpu.add_process(&ProcessDescription::new(duration, None, 0)).unwrap();
+        }
+        cpu.simulate_till_end_of_every_process();
+        cpu
+    }
+
+    #[test]
+    fn balancing_migrates_processes() {
+        let unbalanced = simulate(10_000);
+        assert_eq!(unbalanced.get_migrations_count(), 0);
+        assert!(unbalanced.current_time >= 300);
+        let cpu = simulate(10);
+        assert!(cpu.get_migrations_count() > 0);
+        assert!(cpu.current_time < 300);
+    }
+
+    #[test]
+    fn cores_utilization_adds_up_to_working_time() {
+        for cpu in [simulate(10_000), simulate(10)] {
+            let utilization = cpu.get_cores_utilization();
+            assert_eq!(utilization.len(), 2);
+            assert!(utilization.iter().all(|utilization| (0f64..=1f64).contains(utilization)));
+            assert!((utilization.iter().sum::<f64>() * cpu.current_time as f64 - cpu.get_total_working_time() as f64).abs() < 1e-6);
+            assert_eq!(cpu.get_total_working_time(), 304);
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use std::str::FromStr;
+
+use crate::cpu_access_manager::{self, CpuAccessManager, Process, ProcessDescription, SimulationError, Time};
+use crate::io_device::IoDevice;
+use crate::simulator::Processes;
+use crate::trace::Trace;
+
+// Task releasing job with the same worst case execution time every period, job has to end within relative deadline from its release
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeriodicTask {
+    period: Time,
+    wcet: Time,
+    relative_deadline: Time,
+}
+
+impl PeriodicTask {
+    #[inline]
+    pub fn new(period: Time, wcet: Time, relative_deadline: Time) -> Self {
+        Self { period, wcet, relative_deadline }
+    }
+
+    // Only constrained deadlines are supported, so at most one job of the task is ready at any time
+    pub fn validate(&self) -> Result<(), SimulationError> {
+        if self.wcet == 0 {
+            return Err(SimulationError::InvalidProcess("Task can't have WCET of 0"));
+        }
+        if self.relative_deadline < self.wcet || self.relative_deadline > self.period {
+            return Err(SimulationError::InvalidProcess("Relative deadline of task has to be between its WCET and period"));
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub fn get_period(&self) -> Time {
+        self.period
+    }
+
+    #[inline]
+    pub fn get_wcet(&self) -> Time {
+        self.wcet
+    }
+
+    #[inline]
+    pub fn get_relative_deadline(&self) -> Time {
+        self.relative_deadline
+    }
+
+    #[inline]
+    pub fn get_utilization(&self) -> f64 {
+        self.wcet as f64 / self.period as f64
+    }
+}
+
+// "period:wcet" or "period:wcet:relative deadline", by default deadline equals period
+impl FromStr for PeriodicTask {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parameters = s.split(':').map(|parameter| parameter.trim().parse().map_err(|_| format!("Invalid task parameter: {}", parameter))).collect::<Result<Vec<Time>, _>>()?;
+        let task = match parameters[..] {
+            [period, wcet] => PeriodicTask::new(period, wcet, period),
+            [period, wcet, relative_deadline] => PeriodicTask::new(period, wcet, relative_deadline),
+            _ => return Err(format!("Expected period:wcet[:relative deadline], got: {}", s)),
+        };
+        task.validate().map_err(|err| err.to_string())?;
+        Ok(task)
+    }
+}
+
+// Length after which schedule of synchronously released tasks repeats
+pub fn get_hyperperiod(tasks: &[PeriodicTask]) -> Result<Time, SimulationError> {
+    tasks.iter().try_fold(1 as Time, |hyperperiod, task| {
+        let (mut a, mut b) = (hyperperiod, task.get_period());
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        (hyperperiod / a).checked_mul(task.get_period()).ok_or(SimulationError::TimeOverflow)
+    })
+}
+
+// Jobs of every task released before the horizon, every task releases its first job at 0, priority of job is index of its task
+pub fn generate_jobs(tasks: &[PeriodicTask], horizon: Time) -> Result<Processes, SimulationError> {
+    for task in tasks {
+        task.validate()?;
+    }
+    let mut releases: Vec<(Time, usize)> = tasks.iter().enumerate().flat_map(|(index, task)| std::iter::successors(Some(0 as Time), |release| release.checked_add(task.get_period())).take_while(|release| *release < horizon).map(move |release| (release, index))).collect();
+    releases.sort_unstable();
+    let mut previous_release = 0;
+    Ok(Processes::from(releases.into_iter().map(|(release, index)| {
+        let wait = release - previous_release;
+        previous_release = release;
+        (wait, ProcessDescription::new(tasks[index].get_wcet(), Some(tasks[index].get_relative_deadline()), index as u32).with_period(tasks[index].get_period()))
+    }).collect::<Vec<_>>()))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorityAssignment {
+    RateMonotonic, // Shorter period - higher priority
+    DeadlineMonotonic, // Shorter relative deadline - higher priority
+}
+
+impl PriorityAssignment {
+    // Processes without period or deadline run only when no periodic job is ready, ties are broken by priority and then by arrival
+    #[inline]
+    fn get_key(&self, process: &Process) -> (bool, Time, u32, u32) {
+        let key = match self {
+            PriorityAssignment::RateMonotonic => process.get_period(),
+            PriorityAssignment::DeadlineMonotonic => process.get_lifetime(),
+        };
+        (key.is_none(), key.unwrap_or(0), process.get_priority(), process.get_id())
+    }
+
+    // The same order of tasks as the one in which scheduler prefers their jobs
+    pub fn get_priority_order(&self, tasks: &[PeriodicTask]) -> Vec<usize> {
+        let mut ans: Vec<usize> = (0..tasks.len()).collect();
+        ans.sort_by_key(|index| match self {
+            PriorityAssignment::RateMonotonic => (tasks[*index].get_period(), *index),
+            PriorityAssignment::DeadlineMonotonic => (tasks[*index].get_relative_deadline(), *index),
+        });
+        ans
+    }
+}
+
+// Preemptive scheduler with static priorities derived from periods or relative deadlines of jobs
+#[derive(Debug, Clone)]
+pub struct FixedPriority {
+    queue: Vec<Process>,
+    assignment: PriorityAssignment,
+    is_ending: bool,
+    working_time: Time,
+    waiting_time: Time,
+    total_working_time: Time,
+    total_waiting_time: Time,
+    next_id: u32,
+    current_time: Time,
+    statistics: Vec<cpu_access_manager::ProcessStatistics>,
+    total_required_time: Time,
+    current_id: Option<u32>,
+    context_switch: cpu_access_manager::ContextSwitch,
+    trace: Trace,
+    io_device: IoDevice<Process>,
+}
+
+impl FixedPriority {
+    #[inline]
+    pub fn new(assignment: PriorityAssignment, context_switch_cost: Time) -> Self {
+        Self { queue: Vec::new(), assignment, is_ending: false, working_time: 0, waiting_time: 0, total_working_time: 0, total_waiting_time: 0, next_id: 0, current_time: 0, statistics: Vec::new(), total_required_time: 0, current_id: None, context_switch: cpu_access_manager::ContextSwitch::new(context_switch_cost), trace: Trace::new(), io_device: IoDevice::new() }
+    }
+
+    fn return_from_io(&mut self) {
+        for (mut process, time) in self.io_device.advance_to(self.current_time) {
+            process.end_io(time);
+            self.trace.io_completion(time, process.get_id());
+            self.queue.push(process);
+        }
+    }
+}
+
+impl CpuAccessManager for FixedPriority {
+    fn add_process(&mut self, description: &ProcessDescription) -> Result<u32, SimulationError> {
+        description.validate()?;
+        let total_required_time = self.total_required_time.checked_add(description.get_total_duration()).ok_or(SimulationError::TimeOverflow)?;
+        self.queue.push(Process::new(self.next_id, self.current_time, description));
+        let ans = self.next_id;
+        self.trace.arrival(self.current_time, ans);
+        self.next_id += 1;
+        self.total_required_time = total_required_time;
+        Ok(ans)
+    }
+
+    #[inline]
+    fn is_working(&self) -> bool {
+        !self.queue.is_empty() || self.io_device.is_working()
+    }
+
+    #[inline]
+    fn simulate_one_tick(&mut self) {
+        self.simulate_n_ticks(1)
+    }
+
+    fn simulate_n_ticks(&mut self, mut n: Time) {
+        while n != 0 {
+            self.return_from_io();
+            if self.is_ending {
+                n = n.min(self.total_required_time);
+            }
+            if let Some(index) = self.queue.iter().enumerate().min_by_key(|(_, process)| self.assignment.get_key(process)).map(|(index, _)| index) {
+                let mut current = self.queue.swap_remove(index);
+                let switch_time = self.context_switch.switch_to(current.get_id(), self.io_device.limit(self.current_time, n));
+                n -= switch_time;
+                self.current_time += switch_time;
+                if self.context_switch.is_switching() {
+                    self.queue.push(current);
+                    continue;
+                }
+                let is_new_call = self.current_id != Some(current.get_id());
+                let worked_time = current.work_for(self.current_time, self.io_device.limit(self.current_time, n), is_new_call);
+                if worked_time != 0 {
+                    self.current_id = Some(current.get_id());
+                }
+                n -= worked_time;
+                self.trace.run(current.get_id(), self.current_time, worked_time);
+                self.current_time += worked_time;
+                self.total_required_time -= worked_time;
+                self.total_working_time += worked_time;
+                if current.is_finished() {
+                    self.trace.finish(self.current_time, &current);
+                    self.total_required_time -= current.get_total_time_left();
+                    self.statistics.push(current.finalize());
+                }
+                else if current.is_blocked() {
+                    let io_time = current.start_io();
+                    self.trace.block(self.current_time, &current);
+                    self.io_device.block(self.current_time, current, io_time);
+                    self.current_id = None;
+                }
+                else {
+                    self.queue.push(current);
+                }
+            }
+            else {
+                let idle_time = self.io_device.limit(self.current_time, n);
+                self.waiting_time = idle_time;
+                self.working_time = 0;
+                self.total_waiting_time += self.waiting_time;
+                self.current_time += idle_time;
+                n -= idle_time;
+            }
+        }
+    }
+
+    #[inline]
+    fn get_total_working_time(&self) -> Time {
+        self.total_working_time
+    }
+
+    #[inline]
+    fn get_total_waiting_time(&self) -> Time {
+        self.total_waiting_time
+    }
+
+    #[inline]
+    fn get_working_time(&self) -> Time {
+        self.working_time
+    }
+
+    #[inline]
+    fn get_waiting_time(&self) -> Time {
+        self.waiting_time
+    }
+
+    #[inline]
+    fn get_context_switches_count(&self) -> usize {
+        self.context_switch.get_count()
+    }
+
+    #[inline]
+    fn get_context_switches_time(&self) -> Time {
+        self.context_switch.get_total_time()
+    }
+
+    #[inline]
+    fn get_io_utilization(&self) -> f64 {
+        self.io_device.get_working_time() as f64 / self.current_time.max(1) as f64
+    }
+
+    #[inline]
+    fn enable_trace(&mut self) {
+        self.trace.enable()
+    }
+
+    #[inline]
+    fn get_trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    #[inline]
+    fn simulate_till_end_of_every_process(&mut self) {
+        self.is_ending = true;
+        while self.is_working() {
+            self.simulate_n_ticks(self.total_required_time)
+        }
+        self.is_ending = false;
+    }
+
+    #[inline]
+    fn finalize(mut self: Box<Self>) -> Vec<cpu_access_manager::ProcessStatistics> {
+        self.simulate_till_end_of_every_process();
+        self.statistics
+    }
+}
@@ -0,0 +1,466 @@
+use std::cmp::Reverse;
+
+use crate::cpu_access_manager::{self, CpuAccessManager, Process, ProcessDescription, SimulationError, Time};
+use crate::io_device::IoDevice;
+use crate::trace::Trace;
+
+// Process together with estimate of its current processor burst, scheduler can't see real burst length
+#[derive(Debug, Clone)]
+struct PredictedProcess {
+    process: Process,
+    estimate: f64,
+    burst_worked: Time,
+}
+
+impl PredictedProcess {
+    // Estimated time till end of the current burst, process which exceeded its estimate is expected to end immediately
+    #[inline]
+    fn get_estimate_left(&self) -> f64 {
+        (self.estimate - self.burst_worked as f64).max(0f64)
+    }
+}
+
+impl PartialEq for PredictedProcess {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl PartialOrd for PredictedProcess {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Eq for PredictedProcess {
+
+}
+
+impl Ord for PredictedProcess {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.get_estimate_left().total_cmp(&other.get_estimate_left()).then(self.process.get_id().cmp(&other.process.get_id()))
+    }
+}
+
+// Exponential averaging of burst lengths: next estimate = alpha * last burst + (1 - alpha) * last estimate
+#[derive(Debug, Clone)]
+struct BurstPredictor {
+    alpha: f64,
+    initial_estimate: f64,
+    total_error: f64,
+    predictions_count: usize,
+}
+
+impl BurstPredictor {
+    fn new(alpha: f64, initial_estimate: f64) -> Result<Self, SimulationError> {
+        if !(0f64..=1f64).contains(&alpha) {
+            return Err(SimulationError::InvalidParameter("Alpha has to be in range [0;1]"));
+        }
+        if initial_estimate < 0f64 {
+            return Err(SimulationError::InvalidParameter("Initial burst estimate cannot be negative"));
+        }
+        Ok(Self { alpha, initial_estimate, total_error: 0f64, predictions_count: 0 })
+    }
+
+    // Nothing is known about a new process, so processes with a single burst all get the same estimate and are ordered by arrival
+    #[inline]
+    fn predict(&self, process: Process) -> PredictedProcess {
+        PredictedProcess { process, estimate: self.initial_estimate, burst_worked: 0 }
+    }
+
+    fn end_burst(&mut self, predicted: &mut PredictedProcess) {
+        let burst = predicted.burst_worked as f64;
+        self.total_error += (burst - predicted.estimate).abs();
+        self.predictions_count += 1;
+        predicted.estimate = self.alpha * burst + (1f64 - self.alpha) * predicted.estimate;
+        predicted.burst_worked = 0;
+    }
+
+    // Average absolute difference between estimated and real length of every completed burst
+    #[inline]
+    fn get_average_error(&self) -> f64 {
+        self.total_error / self.predictions_count.max(1) as f64
+    }
+}
+
+#[derive(Debug, Clone)]
+#[allow(clippy::upper_case_acronyms)]
+pub struct PredictiveSJF {
+    queue: std::collections::BinaryHeap<Reverse<PredictedProcess>>,
+    current_process: Option<PredictedProcess>,
+    predictor: BurstPredictor,
+    is_ending: bool,
+    working_time: Time,
+    waiting_time: Time,
+    total_working_time: Time,
+    total_waiting_time: Time,
+    next_id: u32,
+    current_time: Time,
+    statistics: Vec<cpu_access_manager::ProcessStatistics>,
+    total_required_time: Time,
+    context_switch: cpu_access_manager::ContextSwitch,
+    trace: Trace,
+    io_device: IoDevice<PredictedProcess>,
+}
+
+impl PredictiveSJF {
+    #[inline]
+    pub fn new(alpha: f64, initial_estimate: f64, context_switch_cost: Time) -> Result<Self, SimulationError> {
+        Ok(Self { queue: std::collections::BinaryHeap::new(), current_process: None, predictor: BurstPredictor::new(alpha, initial_estimate)?, is_ending: false, working_time: 0, waiting_time: 0, total_working_time: 0, total_waiting_time: 0, next_id: 0, current_time: 0, statistics: Vec::new(), total_required_time: 0, context_switch: cpu_access_manager::ContextSwitch::new(context_switch_cost), trace: Trace::new(), io_device: IoDevice::new() })
+    }
+
+    fn return_from_io(&mut self) {
+        for (mut predicted, time) in self.io_device.advance_to(self.current_time) {
+            predicted.process.end_io(time);
+            self.trace.io_completion(time, predicted.process.get_id());
+            self.queue.push(Reverse(predicted));
+        }
+    }
+}
+
+impl CpuAccessManager for PredictiveSJF {
+    fn add_process(&mut self, description: &ProcessDescription) -> Result<u32, SimulationError> {
+        description.validate()?;
+        let total_required_time = self.total_required_time.checked_add(description.get_total_duration()).ok_or(SimulationError::TimeOverflow)?;
+        self.queue.push(Reverse(self.predictor.predict(Process::new(self.next_id, self.current_time, description))));
+        let ans = self.next_id;
+        self.trace.arrival(self.current_time, ans);
+        self.next_id += 1;
+        if self.current_process.is_none() {
+            self.current_process = self.queue.pop().map(|v| v.0);
+        }
+        self.total_required_time = total_required_time;
+        Ok(ans)
+    }
+
+    #[inline]
+    fn is_working(&self) -> bool {
+        self.current_process.is_some() || !self.queue.is_empty() || self.io_device.is_working()
+    }
+
+    #[inline]
+    fn simulate_one_tick(&mut self) {
+        self.simulate_n_ticks(1)
+    }
+
+    fn simulate_n_ticks(&mut self, mut n: Time) {
+        while n != 0 {
+            self.return_from_io();
+            if self.is_ending {
+                n = n.min(self.total_required_time);
+            }
+            if self.current_process.is_none() {
+                self.current_process = self.queue.pop().map(|v| v.0);
+            }
+            if let Some(mut current) = self.current_process.take() {
+                let switch_time = self.context_switch.switch_to(current.process.get_id(), self.io_device.limit(self.current_time, n));
+                n -= switch_time;
+                self.current_time += switch_time;
+                if self.context_switch.is_switching() {
+                    self.current_process = Some(current);
+                    continue;
+                }
+                let is_new_call = current.process.is_at_burst_start();
+                let worked_time = current.process.work_for(self.current_time, self.io_device.limit(self.current_time, n), is_new_call);
+                n -= worked_time;
+                current.burst_worked += worked_time;
+                self.trace.run(current.process.get_id(), self.current_time, worked_time);
+                self.current_time += worked_time;
+                self.total_required_time -= worked_time;
+                self.total_working_time += worked_time;
+                if current.process.is_finished() {
+                    if current.process.is_successful() == Some(true) {
+                        self.predictor.end_burst(&mut current);
+                    }
+                    self.trace.finish(self.current_time, &current.process);
+                    self.total_required_time -= current.process.get_total_time_left();
+                    self.statistics.push(current.process.finalize());
+                    self.current_process = self.queue.pop().map(|v| v.0);
+                }
+                else if current.process.is_blocked() {
+                    self.predictor.end_burst(&mut current);
+                    let io_time = current.process.start_io();
+                    self.trace.block(self.current_time, &current.process);
+                    self.io_device.block(self.current_time, current, io_time);
+                    self.current_process = self.queue.pop().map(|v| v.0);
+                }
+                else {
+                    self.current_process = Some(current);
+                }
+            }
+            else {
+                let idle_time = self.io_device.limit(self.current_time, n);
+                self.waiting_time = idle_time;
+                self.working_time = 0;
+                self.total_waiting_time += self.waiting_time;
+                self.current_time += idle_time;
+                n -= idle_time;
+            }
+        }
+    }
+
+    #[inline]
+    fn get_total_working_time(&self) -> Time {
+        self.total_working_time
+    }
+
+    #[inline]
+    fn get_total_waiting_time(&self) -> Time {
+        self.total_waiting_time
+    }
+
+    #[inline]
+    fn get_working_time(&self) -> Time {
+        self.working_time
+    }
+
+    #[inline]
+    fn get_waiting_time(&self) -> Time {
+        self.waiting_time
+    }
+
+    #[inline]
+    fn get_context_switches_count(&self) -> usize {
+        self.context_switch.get_count()
+    }
+
+    #[inline]
+    fn get_context_switches_time(&self) -> Time {
+        self.context_switch.get_total_time()
+    }
+
+    #[inline]
+    fn get_io_utilization(&self) -> f64 {
+        self.io_device.get_working_time() as f64 / self.current_time.max(1) as f64
+    }
+
+    #[inline]
+    fn get_prediction_error(&self) -> Option<f64> {
+        Some(self.predictor.get_average_error())
+    }
+
+    #[inline]
+    fn enable_trace(&mut self) {
+        self.trace.enable()
+    }
+
+    #[inline]
+    fn get_trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    #[inline]
+    fn simulate_till_end_of_every_process(&mut self) {
+        self.is_ending = true;
+        while self.is_working() {
+            self.simulate_n_ticks(self.total_required_time)
+        }
+        self.is_ending = false;
+    }
+
+    #[inline]
+    fn finalize(mut self: Box<Self>) -> Vec<cpu_access_manager::ProcessStatistics> {
+        self.simulate_till_end_of_every_process();
+        self.statistics
+    }
+}
+
+// Process with the shortest estimated remaining burst runs, it's checked again whenever something is added to the queue
+#[derive(Debug, Clone)]
+pub struct PredictiveSJFWithPreemption {
+    queue: std::collections::BinaryHeap<Reverse<PredictedProcess>>,
+    predictor: BurstPredictor,
+    is_ending: bool,
+    working_time: Time,
+    waiting_time: Time,
+    total_working_time: Time,
+    total_waiting_time: Time,
+    next_id: u32,
+    current_time: Time,
+    statistics: Vec<cpu_access_manager::ProcessStatistics>,
+    total_required_time: Time,
+    current_id: Option<u32>,
+    context_switch: cpu_access_manager::ContextSwitch,
+    trace: Trace,
+    io_device: IoDevice<PredictedProcess>,
+}
+
+impl PredictiveSJFWithPreemption {
+    #[inline]
+    pub fn new(alpha: f64, initial_estimate: f64, context_switch_cost: Time) -> Result<Self, SimulationError> {
+        Ok(Self { queue: std::collections::BinaryHeap::new(), predictor: BurstPredictor::new(alpha, initial_estimate)?, is_ending: false, working_time: 0, waiting_time: 0, total_working_time: 0, total_waiting_time: 0, next_id: 0, current_time: 0, statistics: Vec::new(), total_required_time: 0, current_id: None, context_switch: cpu_access_manager::ContextSwitch::new(context_switch_cost), trace: Trace::new(), io_device: IoDevice::new() })
+    }
+
+    fn return_from_io(&mut self) {
+        for (mut predicted, time) in self.io_device.advance_to(self.current_time) {
+            predicted.process.end_io(time);
+            self.trace.io_completion(time, predicted.process.get_id());
+            self.queue.push(Reverse(predicted));
+        }
+    }
+}
+
+impl CpuAccessManager for PredictiveSJFWithPreemption {
+    fn add_process(&mut self, description: &ProcessDescription) -> Result<u32, SimulationError> {
+        description.validate()?;
+        let total_required_time = self.total_required_time.checked_add(description.get_total_duration()).ok_or(SimulationError::TimeOverflow)?;
+        self.queue.push(Reverse(self.predictor.predict(Process::new(self.next_id, self.current_time, description))));
+        let ans = self.next_id;
+        self.trace.arrival(self.current_time, ans);
+        self.next_id += 1;
+        self.total_required_time = total_required_time;
+        Ok(ans)
+    }
+
+    #[inline]
+    fn is_working(&self) -> bool {
+        !self.queue.is_empty() || self.io_device.is_working()
+    }
+
+    #[inline]
+    fn simulate_one_tick(&mut self) {
+        self.simulate_n_ticks(1)
+    }
+
+    fn simulate_n_ticks(&mut self, mut n: Time) {
+        while n != 0 {
+            self.return_from_io();
+            if self.is_ending {
+                n = n.min(self.total_required_time);
+            }
+            if let Some(Reverse(mut current)) = self.queue.pop() {
+                let switch_time = self.context_switch.switch_to(current.process.get_id(), self.io_device.limit(self.current_time, n));
+                n -= switch_time;
+                self.current_time += switch_time;
+                if self.context_switch.is_switching() {
+                    self.queue.push(Reverse(current));
+                    continue;
+                }
+                let is_new_call = self.current_id != Some(current.process.get_id());
+                let worked_time = current.process.work_for(self.current_time, self.io_device.limit(self.current_time, n), is_new_call);
+                if worked_time != 0 {
+                    self.current_id = Some(current.process.get_id());
+                }
+                n -= worked_time;
+                current.burst_worked += worked_time;
+                self.trace.run(current.process.get_id(), self.current_time, worked_time);
+                self.current_time += worked_time;
+                self.total_required_time -= worked_time;
+                self.total_working_time += worked_time;
+                if current.process.is_finished() {
+                    if current.process.is_successful() == Some(true) {
+                        self.predictor.end_burst(&mut current);
+                    }
+                    self.trace.finish(self.current_time, &current.process);
+                    self.total_required_time -= current.process.get_total_time_left();
+                    self.statistics.push(current.process.finalize());
+                }
+                else if current.process.is_blocked() {
+                    self.predictor.end_burst(&mut current);
+                    let io_time = current.process.start_io();
+                    self.trace.block(self.current_time, &current.process);
+                    self.io_device.block(self.current_time, current, io_time);
+                    self.current_id = None;
+                }
+                else {
+                    self.queue.push(Reverse(current));
+                }
+            }
+            else {
+                let idle_time = self.io_device.limit(self.current_time, n);
+                self.waiting_time = idle_time;
+                self.working_time = 0;
+                self.total_waiting_time += self.waiting_time;
+                self.current_time += idle_time;
+                n -= idle_time;
+            }
+        }
+    }
+
+    #[inline]
+    fn get_total_working_time(&self) -> Time {
+        self.total_working_time
+    }
+
+    #[inline]
+    fn get_total_waiting_time(&self) -> Time {
+        self.total_waiting_time
+    }
+
+    #[inline]
+    fn get_working_time(&self) -> Time {
+        self.working_time
+    }
+
+    #[inline]
+    fn get_waiting_time(&self) -> Time {
+        self.waiting_time
+    }
+
+    #[inline]
+    fn get_context_switches_count(&self) -> usize {
+        self.context_switch.get_count()
+    }
+
+    #[inline]
+    fn get_context_switches_time(&self) -> Time {
+        self.context_switch.get_total_time()
+    }
+
+    #[inline]
+    fn get_io_utilization(&self) -> f64 {
+        self.io_device.get_working_time() as f64 / self.current_time.max(1) as f64
+    }
+
+    #[inline]
+    fn get_prediction_error(&self) -> Option<f64> {
+        Some(self.predictor.get_average_error())
+    }
+
+    #[inline]
+    fn enable_trace(&mut self) {
+        self.trace.enable()
+    }
+
+    #[inline]
+    fn get_trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    #[inline]
+    fn simulate_till_end_of_every_process(&mut self) {
+        self.is_ending = true;
+        while self.is_working() {
+            self.simulate_n_ticks(self.total_required_time)
+        }
+        self.is_ending = false;
+    }
+
+    #[inline]
+    fn finalize(mut self: Box<Self>) -> Vec<cpu_access_manager::ProcessStatistics> {
+        self.simulate_till_end_of_every_process();
+        self.statistics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimates_move_toward_real_bursts() {
+        let mut predictor = BurstPredictor::new(0.5, 50f64).unwrap();
+        let mut predicted = predictor.predict(Process::new(0, 0, &ProcessDescription::new(10, None, 0)));
+        let estimates: Vec<f64> = (0..4).map(|_| {
+            predicted.burst_worked = 10;
+            predictor.end_burst(&mut predicted);
+            predicted.estimate
+        }).collect();
+        assert_eq!(estimates, [30f64, 20f64, 15f64, 12.5]);
+        // Errors of 5 bursts of 10 ticks with estimates 50, 30, 20, 15 and 12.5
+        let mut cpu = PredictiveSJF::new(0.5, 50f64, 0).unwrap();
+        cpu.add_process(&ProcessDescription::new(10, None, 0).with_io_bursts(vec![(5, 10); 4])).unwrap();
+        cpu.simulate_till_end_of_every_process();
+        assert_eq!(cpu.get_prediction_error(), Some(15.5));
+    }
+}
@@ -0,0 +1,403 @@
+use crate::cpu_access_manager::{self, CpuAccessManager, Process, ProcessDescription, SimulationError, Time};
+use crate::io_device::IoDevice;
+use crate::trace::Trace;
+
+// Index of the process with the best effective priority, older processes win ties
+fn select_process(queue: &[Process], current_time: Time, aging_period: Option<Time>) -> Option<usize> {
+    queue.iter().enumerate().min_by_key(|(_, process)| (process.get_effective_priority(current_time, aging_period), process.get_id())).map(|(index, _)| index)
+}
+
+#[derive(Debug, Clone)]
+pub struct Priority {
+    queue: Vec<Process>,
+    current_process: Option<Process>,
+    aging_period: Option<Time>,
+    is_ending: bool,
+    working_time: Time,
+    waiting_time: Time,
+    total_working_time: Time,
+    total_waiting_time: Time,
+    next_id: u32,
+    current_time: Time,
+    statistics: Vec<cpu_access_manager::ProcessStatistics>,
+    total_required_time: Time,
+    context_switch: cpu_access_manager::ContextSwitch,
+    trace: Trace,
+    io_device: IoDevice<Process>,
+}
+
+impl Priority {
+    #[inline]
+    pub fn new(aging_period: Option<Time>, context_switch_cost: Time) -> Result<Self, SimulationError> {
+        if aging_period == Some(0) {
+            return Err(SimulationError::InvalidParameter("Aging period cannot be 0"));
+        }
+        Ok(Self { queue: Vec::new(), current_process: None, aging_period, is_ending: false, working_time: 0, waiting_time: 0, total_working_time: 0, total_waiting_time: 0, next_id: 0, current_time: 0, statistics: Vec::new(), total_required_time: 0, context_switch: cpu_access_manager::ContextSwitch::new(context_switch_cost), trace: Trace::new(), io_device: IoDevice::new() })
+    }
+
+    #[inline]
+    fn pop_next(&mut self) -> Option<Process> {
+        select_process(&self.queue, self.current_time, self.aging_period).map(|index| self.queue.swap_remove(index))
+    }
+
+    fn return_from_io(&mut self) {
+        for (mut process, time) in self.io_device.advance_to(self.current_time) {
+            process.end_io(time);
+            self.trace.io_completion(time, process.get_id());
+            self.queue.push(process);
+        }
+    }
+}
+
+impl CpuAccessManager for Priority {
+    fn add_process(&mut self, description: &ProcessDescription) -> Result<u32, SimulationError> {
+        description.validate()?;
+        let total_required_time = self.total_required_time.checked_add(description.get_total_duration()).ok_or(SimulationError::TimeOverflow)?;
+        self.queue.push(Process::new(self.next_id, self.current_time, description));
+        let ans = self.next_id;
+        self.trace.arrival(self.current_time, ans);
+        self.next_id += 1;
+        if self.current_process.is_none() {
+            self.current_process = self.pop_next();
+        }
+        self.total_required_time = total_required_time;
+        Ok(ans)
+    }
+
+    #[inline]
+    fn is_working(&self) -> bool {
+        self.current_process.is_some() || !self.queue.is_empty() || self.io_device.is_working()
+    }
+
+    #[inline]
+    fn simulate_one_tick(&mut self) {
+        self.simulate_n_ticks(1)
+    }
+
+    fn simulate_n_ticks(&mut self, mut n: Time) {
+        while n != 0 {
+            self.return_from_io();
+            if self.is_ending {
+                n = n.min(self.total_required_time);
+            }
+            if self.current_process.is_none() {
+                self.current_process = self.pop_next();
+            }
+            if let Some(mut current) = self.current_process.take() {
+                let switch_time = self.context_switch.switch_to(current.get_id(), self.io_device.limit(self.current_time, n));
+                n -= switch_time;
+                self.current_time += switch_time;
+                if self.context_switch.is_switching() {
+                    self.current_process = Some(current);
+                    continue;
+                }
+                let worked_time = current.work_for(self.current_time, self.io_device.limit(self.current_time, n), current.is_at_burst_start());
+                n -= worked_time;
+                self.trace.run(current.get_id(), self.current_time, worked_time);
+                self.current_time += worked_time;
+                self.total_required_time -= worked_time;
+                self.total_working_time += worked_time;
+                if current.is_finished() {
+                    self.trace.finish(self.current_time, &current);
+                    self.total_required_time -= current.get_total_time_left();
+                    self.statistics.push(current.finalize());
+                    self.current_process = self.pop_next();
+                }
+                else if current.is_blocked() {
+                    let io_time = current.start_io();
+                    self.trace.block(self.current_time, &current);
+                    self.io_device.block(self.current_time, current, io_time);
+                    self.current_process = self.pop_next();
+                }
+                else {
+                    self.current_process = Some(current);
+                }
+            }
+            else {
+                let idle_time = self.io_device.limit(self.current_time, n);
+                self.waiting_time = idle_time;
+                self.working_time = 0;
+                self.total_waiting_time += self.waiting_time;
+                self.current_time += idle_time;
+                n -= idle_time;
+            }
+        }
+    }
+
+    #[inline]
+    fn get_total_working_time(&self) -> Time {
+        self.total_working_time
+    }
+
+    #[inline]
+    fn get_total_waiting_time(&self) -> Time {
+        self.total_waiting_time
+    }
+
+    #[inline]
+    fn get_working_time(&self) -> Time {
+        self.working_time
+    }
+
+    #[inline]
+    fn get_waiting_time(&self) -> Time {
+        self.waiting_time
+    }
+
+    #[inline]
+    fn get_context_switches_count(&self) -> usize {
+        self.context_switch.get_count()
+    }
+
+    #[inline]
+    fn get_context_switches_time(&self) -> Time {
+        self.context_switch.get_total_time()
+    }
+
+    #[inline]
+    fn get_io_utilization(&self) -> f64 {
+        self.io_device.get_working_time() as f64 / self.current_time.max(1) as f64
+    }
+
+    #[inline]
+    fn enable_trace(&mut self) {
+        self.trace.enable()
+    }
+
+    #[inline]
+    fn get_trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    #[inline]
+    fn simulate_till_end_of_every_process(&mut self) {
+        self.is_ending = true;
+        while self.is_working() {
+            self.simulate_n_ticks(self.total_required_time)
+        }
+        self.is_ending = false;
+    }
+
+    #[inline]
+    fn finalize(mut self: Box<Self>) -> Vec<cpu_access_manager::ProcessStatistics> {
+        self.simulate_till_end_of_every_process();
+        self.statistics
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PriorityWithPreemption {
+    queue: Vec<Process>,
+    aging_period: Option<Time>,
+    is_ending: bool,
+    working_time: Time,
+    waiting_time: Time,
+    total_working_time: Time,
+    total_waiting_time: Time,
+    next_id: u32,
+    current_time: Time,
+    statistics: Vec<cpu_access_manager::ProcessStatistics>,
+    total_required_time: Time,
+    current_id: Option<u32>,
+    is_new_call: bool,
+    time_left_for_current_process: Time, // Until some waiting process ages, 0 - choose again
+    context_switch: cpu_access_manager::ContextSwitch,
+    trace: Trace,
+    io_device: IoDevice<Process>,
+}
+
+impl PriorityWithPreemption {
+    #[inline]
+    pub fn new(aging_period: Option<Time>, context_switch_cost: Time) -> Result<Self, SimulationError> {
+        if aging_period == Some(0) {
+            return Err(SimulationError::InvalidParameter("Aging period cannot be 0"));
+        }
+        Ok(Self { queue: Vec::new(), aging_period, is_ending: false, working_time: 0, waiting_time: 0, total_working_time: 0, total_waiting_time: 0, next_id: 0, current_time: 0, statistics: Vec::new(), total_required_time: 0, current_id: None, is_new_call: false, time_left_for_current_process: 0, context_switch: cpu_access_manager::ContextSwitch::new(context_switch_cost), trace: Trace::new(), io_device: IoDevice::new() })
+    }
+
+    fn return_from_io(&mut self) {
+        for (mut process, time) in self.io_device.advance_to(self.current_time) {
+            process.end_io(time);
+            self.trace.io_completion(time, process.get_id());
+            self.queue.push(process);
+            self.time_left_for_current_process = 0;
+        }
+    }
+
+    // Waiting processes may overtake the current one as soon as any of them ages
+    fn get_time_to_next_aging(&self) -> Time {
+        self.queue.iter().filter_map(|process| process.get_time_to_next_aging(self.current_time, self.aging_period)).fold(Time::MAX, Time::min)
+    }
+}
+
+impl CpuAccessManager for PriorityWithPreemption {
+    fn add_process(&mut self, description: &ProcessDescription) -> Result<u32, SimulationError> {
+        description.validate()?;
+        let total_required_time = self.total_required_time.checked_add(description.get_total_duration()).ok_or(SimulationError::TimeOverflow)?;
+        self.queue.push(Process::new(self.next_id, self.current_time, description));
+        self.time_left_for_current_process = 0;
+        let ans = self.next_id;
+        self.trace.arrival(self.current_time, ans);
+        self.next_id += 1;
+        self.total_required_time = total_required_time;
+        Ok(ans)
+    }
+
+    #[inline]
+    fn is_working(&self) -> bool {
+        !self.queue.is_empty() || self.io_device.is_working()
+    }
+
+    #[inline]
+    fn simulate_one_tick(&mut self) {
+        self.simulate_n_ticks(1)
+    }
+
+    fn simulate_n_ticks(&mut self, mut n: Time) {
+        while n != 0 {
+            self.return_from_io();
+            if self.is_ending {
+                n = n.min(self.total_required_time);
+            }
+            // Running process keeps processor till some waiting one ages, even if simulation is split into many calls
+            let current_index = self.current_id.filter(|_| self.time_left_for_current_process != 0).and_then(|id| self.queue.iter().position(|process| process.get_id() == id));
+            if let Some(index) = current_index.or_else(|| select_process(&self.queue, self.current_time, self.aging_period)) {
+                let mut current = self.queue.swap_remove(index);
+                let switch_time = self.context_switch.switch_to(current.get_id(), self.io_device.limit(self.current_time, n).min(self.get_time_to_next_aging()));
+                n -= switch_time;
+                self.current_time += switch_time;
+                if self.context_switch.is_switching() {
+                    self.queue.push(current);
+                    self.time_left_for_current_process = 0;
+                    continue;
+                }
+                if current_index.is_none() {
+                    self.time_left_for_current_process = self.get_time_to_next_aging();
+                }
+                let time_for_process = self.io_device.limit(self.current_time, n).min(self.time_left_for_current_process);
+                if self.current_id != Some(current.get_id()) {
+                    self.current_id = Some(current.get_id());
+                    self.is_new_call = true;
+                }
+                let worked_time = current.work_for(self.current_time, time_for_process, self.is_new_call);
+                if worked_time != 0 {
+                    self.is_new_call = false;
+                }
+                n -= worked_time;
+                self.time_left_for_current_process -= worked_time;
+                self.trace.run(current.get_id(), self.current_time, worked_time);
+                self.current_time += worked_time;
+                self.total_required_time -= worked_time;
+                self.total_working_time += worked_time;
+                if current.is_finished() {
+                    self.trace.finish(self.current_time, &current);
+                    self.total_required_time -= current.get_total_time_left();
+                    self.statistics.push(current.finalize());
+                }
+                else if current.is_blocked() {
+                    let io_time = current.start_io();
+                    self.trace.block(self.current_time, &current);
+                    self.io_device.block(self.current_time, current, io_time);
+                    self.current_id = None;
+                }
+                else {
+                    self.queue.push(current);
+                }
+            }
+            else {
+                let idle_time = self.io_device.limit(self.current_time, n);
+                self.waiting_time = idle_time;
+                self.working_time = 0;
+                self.total_waiting_time += self.waiting_time;
+                self.current_time += idle_time;
+                n -= idle_time;
+            }
+        }
+    }
+
+    #[inline]
+    fn get_total_working_time(&self) -> Time {
+        self.total_working_time
+    }
+
+    #[inline]
+    fn get_total_waiting_time(&self) -> Time {
+        self.total_waiting_time
+    }
+
+    #[inline]
+    fn get_working_time(&self) -> Time {
+        self.working_time
+    }
+
+    #[inline]
+    fn get_waiting_time(&self) -> Time {
+        self.waiting_time
+    }
+
+    #[inline]
+    fn get_context_switches_count(&self) -> usize {
+        self.context_switch.get_count()
+    }
+
+    #[inline]
+    fn get_context_switches_time(&self) -> Time {
+        self.context_switch.get_total_time()
+    }
+
+    #[inline]
+    fn get_io_utilization(&self) -> f64 {
+        self.io_device.get_working_time() as f64 / self.current_time.max(1) as f64
+    }
+
+    #[inline]
+    fn enable_trace(&mut self) {
+        self.trace.enable()
+    }
+
+    #[inline]
+    fn get_trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    #[inline]
+    fn simulate_till_end_of_every_process(&mut self) {
+        self.is_ending = true;
+        while self.is_working() {
+            self.simulate_n_ticks(self.total_required_time)
+        }
+        self.is_ending = false;
+    }
+
+    #[inline]
+    fn finalize(mut self: Box<Self>) -> Vec<cpu_access_manager::ProcessStatistics> {
+        self.simulate_till_end_of_every_process();
+        self.statistics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::simulator::{self, Processes, SimulationSettings};
+
+    // High priority processes keep arriving faster than they are served, so without aging the low priority one waits for all of them
+    #[test]
+    fn aging_prevents_starvation() {
+        let workload = Processes::from([(0, 10, None, 0), (0, 10, Some(200), 3)].into_iter().chain((0..50).map(|_| (5, 10, None, 0))).collect::<Vec<_>>());
+        for algorithm in ["Priority", "Priority with preemption"] {
+            let simulate = |aging_period| simulator::simulate_every(workload.clone(), &SimulationSettings { aging_period, algorithms: Some(vec![algorithm.to_owned()]), ..SimulationSettings::default() }).unwrap().remove(0).1;
+            let without_aging = simulate(None);
+            let with_aging = simulate(Some(20));
+            let low = |statistics: &simulator::SimulationStatistics| statistics.get_priority_classes()[&3].get_longest_waiting_time();
+            let high = |statistics: &simulator::SimulationStatistics| statistics.get_priority_classes()[&0].get_average_waiting_time();
+            assert_eq!((without_aging.get_priority_classes()[&3].get_processes_count(), without_aging.get_priority_classes()[&0].get_processes_count()), (1, 51));
+            // Without aging low priority process runs after every other one, long after its lifetime
+            assert!(low(&without_aging) >= 500, "{}: {}", algorithm, low(&without_aging));
+            assert_eq!(without_aging.get_successful_processes_count(), 51);
+            // With aging it gets the processor within its lifetime, high priority processes wait a bit longer for that
+            assert!(low(&with_aging) < 200, "{}: {}", algorithm, low(&with_aging));
+            assert_eq!(with_aging.get_successful_processes_count(), 52);
+            assert!(high(&with_aging) > high(&without_aging));
+        }
+    }
+}
@@ -0,0 +1,482 @@
+use std::collections::BTreeMap;
+
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use crate::cpu_access_manager::{self, CpuAccessManager, FairnessTracker, Process, ProcessDescription, ProcessShare, SimulationError, Time};
+use crate::io_device::IoDevice;
+use crate::trace::Trace;
+
+// Every quantum a lottery is drawn among ready processes, chance of winning is proportional to process tickets
+#[derive(Debug, Clone)]
+pub struct Lottery {
+    quantum_time: Time,
+    queue: Vec<Process>,
+    current_process: Option<Process>,
+    time_left_for_current_process: Time,
+    rng: StdRng,
+    is_ending: bool,
+    working_time: Time,
+    waiting_time: Time,
+    total_working_time: Time,
+    total_waiting_time: Time,
+    next_id: u32,
+    current_time: Time,
+    statistics: Vec<cpu_access_manager::ProcessStatistics>,
+    total_required_time: Time,
+    context_switch: cpu_access_manager::ContextSwitch,
+    trace: Trace,
+    io_device: IoDevice<Process>,
+    fairness: FairnessTracker,
+}
+
+impl Lottery {
+    // Without seed every run draws different lotteries
+    pub fn new(quantum_time: Time, seed: Option<u64>, context_switch_cost: Time) -> Result<Self, SimulationError> {
+        if quantum_time == 0 {
+            return Err(SimulationError::InvalidParameter("Quant cannot be 0"));
+        }
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Ok(Self { quantum_time, queue: Vec::new(), current_process: None, time_left_for_current_process: quantum_time, rng, is_ending: false, working_time: 0, waiting_time: 0, total_working_time: 0, total_waiting_time: 0, next_id: 0, current_time: 0, statistics: Vec::new(), total_required_time: 0, context_switch: cpu_access_manager::ContextSwitch::new(context_switch_cost), trace: Trace::new(), io_device: IoDevice::new(), fairness: FairnessTracker::new() })
+    }
+
+    fn return_from_io(&mut self) {
+        for (mut process, time) in self.io_device.advance_to(self.current_time) {
+            process.end_io(time);
+            self.trace.io_completion(time, process.get_id());
+            self.fairness.make_ready(process.get_id(), process.get_tickets() as u64);
+            self.queue.push(process);
+        }
+    }
+
+    fn draw(&mut self) -> Option<Process> {
+        let total_tickets = self.queue.iter().map(|process| process.get_tickets() as u64).sum::<u64>();
+        if total_tickets == 0 {
+            return None;
+        }
+        let mut winning_ticket = self.rng.gen_range(0..total_tickets);
+        let index = self.queue.iter().position(|process| match winning_ticket < process.get_tickets() as u64 {
+            true => true,
+            false => {
+                winning_ticket -= process.get_tickets() as u64;
+                false
+            },
+        }).unwrap();
+        self.time_left_for_current_process = self.quantum_time;
+        Some(self.queue.swap_remove(index))
+    }
+}
+
+impl CpuAccessManager for Lottery {
+    fn add_process(&mut self, description: &ProcessDescription) -> Result<u32, SimulationError> {
+        description.validate()?;
+        let total_required_time = self.total_required_time.checked_add(description.get_total_duration()).ok_or(SimulationError::TimeOverflow)?;
+        self.queue.push(Process::new(self.next_id, self.current_time, description));
+        let ans = self.next_id;
+        self.trace.arrival(self.current_time, ans);
+        self.fairness.make_ready(ans, description.get_tickets() as u64);
+        self.next_id += 1;
+        self.total_required_time = total_required_time;
+        Ok(ans)
+    }
+
+    #[inline]
+    fn is_working(&self) -> bool {
+        self.current_process.is_some() || !self.queue.is_empty() || self.io_device.is_working()
+    }
+
+    #[inline]
+    fn simulate_one_tick(&mut self) {
+        self.simulate_n_ticks(1)
+    }
+
+    fn simulate_n_ticks(&mut self, mut n: Time) {
+        while n != 0 {
+            self.return_from_io();
+            if self.is_ending {
+                n = n.min(self.total_required_time);
+            }
+            if self.current_process.is_none() {
+                self.current_process = self.draw();
+            }
+            if let Some(mut current) = self.current_process.take() {
+                let switch_time = self.context_switch.switch_to(current.get_id(), self.io_device.limit(self.current_time, n));
+                n -= switch_time;
+                self.current_time += switch_time;
+                if self.context_switch.is_switching() {
+                    self.current_process = Some(current);
+                    continue;
+                }
+                let time_for_process = self.io_device.limit(self.current_time, n).min(self.time_left_for_current_process);
+                let worked_time = current.work_for(self.current_time, time_for_process, self.time_left_for_current_process == self.quantum_time);
+                n -= worked_time;
+                self.time_left_for_current_process -= worked_time;
+                self.trace.run(current.get_id(), self.current_time, worked_time);
+                self.fairness.run(current.get_id(), worked_time);
+                self.current_time += worked_time;
+                self.total_required_time -= worked_time;
+                self.total_working_time += worked_time;
+                if current.is_finished() {
+                    self.trace.finish(self.current_time, &current);
+                    self.fairness.finish(current.get_id());
+                    self.total_required_time -= current.get_total_time_left();
+                    self.statistics.push(current.finalize());
+                }
+                else if current.is_blocked() {
+                    let io_time = current.start_io();
+                    self.trace.block(self.current_time, &current);
+                    self.fairness.leave(current.get_id());
+                    self.io_device.block(self.current_time, current, io_time);
+                }
+                else if self.time_left_for_current_process == 0 {
+                    self.queue.push(current);
+                }
+                else {
+                    self.current_process = Some(current);
+                }
+            }
+            else {
+                let idle_time = self.io_device.limit(self.current_time, n);
+                self.waiting_time = idle_time;
+                self.working_time = 0;
+                self.total_waiting_time += self.waiting_time;
+                self.current_time += idle_time;
+                n -= idle_time;
+            }
+        }
+    }
+
+    #[inline]
+    fn get_total_working_time(&self) -> Time {
+        self.total_working_time
+    }
+
+    #[inline]
+    fn get_total_waiting_time(&self) -> Time {
+        self.total_waiting_time
+    }
+
+    #[inline]
+    fn get_working_time(&self) -> Time {
+        self.working_time
+    }
+
+    #[inline]
+    fn get_waiting_time(&self) -> Time {
+        self.waiting_time
+    }
+
+    #[inline]
+    fn get_context_switches_count(&self) -> usize {
+        self.context_switch.get_count()
+    }
+
+    #[inline]
+    fn get_context_switches_time(&self) -> Time {
+        self.context_switch.get_total_time()
+    }
+
+    #[inline]
+    fn get_io_utilization(&self) -> f64 {
+        self.io_device.get_working_time() as f64 / self.current_time.max(1) as f64
+    }
+
+    #[inline]
+    fn get_fairness_deviation(&self) -> Option<f64> {
+        Some(self.fairness.get_deviation())
+    }
+
+    #[inline]
+    fn get_processes_shares(&self) -> Vec<ProcessShare> {
+        self.fairness.get_shares().to_vec()
+    }
+
+    #[inline]
+    fn enable_trace(&mut self) {
+        self.trace.enable()
+    }
+
+    #[inline]
+    fn get_trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    #[inline]
+    fn simulate_till_end_of_every_process(&mut self) {
+        self.is_ending = true;
+        while self.is_working() {
+            self.simulate_n_ticks(self.total_required_time)
+        }
+        self.is_ending = false;
+    }
+
+    #[inline]
+    fn finalize(mut self: Box<Self>) -> Vec<cpu_access_manager::ProcessStatistics> {
+        self.simulate_till_end_of_every_process();
+        self.statistics
+    }
+}
+
+// Large number divided by tickets, so strides of processes with few tickets are still precise, tickets can't exceed it so no stride is 0
+pub const STRIDE_ONE: u64 = 1 << 20;
+
+// Deterministic counterpart of lottery, process with the smallest pass runs for a quantum and its pass grows by its stride
+#[derive(Debug, Clone)]
+pub struct Stride {
+    quantum_time: Time,
+    queue: BTreeMap<(u64, u32), Process>,
+    current_process: Option<(Process, u64)>,
+    time_left_for_current_process: Time,
+    min_pass: u64,
+    is_ending: bool,
+    working_time: Time,
+    waiting_time: Time,
+    total_working_time: Time,
+    total_waiting_time: Time,
+    next_id: u32,
+    current_time: Time,
+    statistics: Vec<cpu_access_manager::ProcessStatistics>,
+    total_required_time: Time,
+    context_switch: cpu_access_manager::ContextSwitch,
+    trace: Trace,
+    io_device: IoDevice<(Process, u64)>,
+    fairness: FairnessTracker,
+}
+
+impl Stride {
+    pub fn new(quantum_time: Time, context_switch_cost: Time) -> Result<Self, SimulationError> {
+        if quantum_time == 0 {
+            return Err(SimulationError::InvalidParameter("Quant cannot be 0"));
+        }
+        Ok(Self { quantum_time, queue: BTreeMap::new(), current_process: None, time_left_for_current_process: quantum_time, min_pass: 0, is_ending: false, working_time: 0, waiting_time: 0, total_working_time: 0, total_waiting_time: 0, next_id: 0, current_time: 0, statistics: Vec::new(), total_required_time: 0, context_switch: cpu_access_manager::ContextSwitch::new(context_switch_cost), trace: Trace::new(), io_device: IoDevice::new(), fairness: FairnessTracker::new() })
+    }
+
+    // Process can't save up pass while it's blocked, otherwise it would monopolize processor after return
+    fn return_from_io(&mut self) {
+        for ((mut process, pass), time) in self.io_device.advance_to(self.current_time) {
+            process.end_io(time);
+            self.trace.io_completion(time, process.get_id());
+            self.fairness.make_ready(process.get_id(), process.get_tickets() as u64);
+            self.queue.insert((pass.max(self.min_pass), process.get_id()), process);
+        }
+    }
+
+    fn update_min_pass(&mut self) {
+        let smallest = self.queue.keys().next().map(|(pass, _)| *pass);
+        let current = self.current_process.as_ref().map(|(_, pass)| *pass);
+        if let Some(pass) = [smallest, current].into_iter().flatten().min() {
+            self.min_pass = self.min_pass.max(pass);
+        }
+    }
+}
+
+impl CpuAccessManager for Stride {
+    fn add_process(&mut self, description: &ProcessDescription) -> Result<u32, SimulationError> {
+        description.validate()?;
+        if description.get_tickets() as u64 > STRIDE_ONE {
+            return Err(SimulationError::InvalidProcess("Process can't have more tickets than STRIDE_ONE in stride scheduling"));
+        }
+        let total_required_time = self.total_required_time.checked_add(description.get_total_duration()).ok_or(SimulationError::TimeOverflow)?;
+        self.queue.insert((self.min_pass, self.next_id), Process::new(self.next_id, self.current_time, description));
+        let ans = self.next_id;
+        self.trace.arrival(self.current_time, ans);
+        self.fairness.make_ready(ans, description.get_tickets() as u64);
+        self.next_id += 1;
+        self.total_required_time = total_required_time;
+        Ok(ans)
+    }
+
+    #[inline]
+    fn is_working(&self) -> bool {
+        self.current_process.is_some() || !self.queue.is_empty() || self.io_device.is_working()
+    }
+
+    #[inline]
+    fn simulate_one_tick(&mut self) {
+        self.simulate_n_ticks(1)
+    }
+
+    fn simulate_n_ticks(&mut self, mut n: Time) {
+        while n != 0 {
+            self.return_from_io();
+            if self.is_ending {
+                n = n.min(self.total_required_time);
+            }
+            if self.current_process.is_none() {
+                self.current_process = self.queue.pop_first().map(|((pass, _), process)| (process, pass));
+                self.time_left_for_current_process = self.quantum_time;
+            }
+            if let Some((mut current, mut pass)) = self.current_process.take() {
+                let switch_time = self.context_switch.switch_to(current.get_id(), self.io_device.limit(self.current_time, n));
+                n -= switch_time;
+                self.current_time += switch_time;
+                if self.context_switch.is_switching() {
+                    self.current_process = Some((current, pass));
+                    continue;
+                }
+                let time_for_process = self.io_device.limit(self.current_time, n).min(self.time_left_for_current_process);
+                let worked_time = current.work_for(self.current_time, time_for_process, self.time_left_for_current_process == self.quantum_time);
+                n -= worked_time;
+                // Process which used only part of quantum is charged only for that part, the same however the quantum was split into calls
+                let charge = |used: Time| (STRIDE_ONE / current.get_tickets() as u64).saturating_mul(used) / self.quantum_time;
+                let used_time = self.quantum_time - self.time_left_for_current_process;
+                pass += charge(used_time + worked_time) - charge(used_time);
+                self.time_left_for_current_process -= worked_time;
+                self.trace.run(current.get_id(), self.current_time, worked_time);
+                self.fairness.run(current.get_id(), worked_time);
+                self.current_time += worked_time;
+                self.total_required_time -= worked_time;
+                self.total_working_time += worked_time;
+                // Minimum includes the time just used also when process leaves the processor, so it doesn't depend on how simulation is split into calls
+                self.min_pass = self.min_pass.max(self.queue.keys().next().map_or(pass, |(smallest, _)| pass.min(*smallest)));
+                if current.is_finished() {
+                    self.trace.finish(self.current_time, &current);
+                    self.fairness.finish(current.get_id());
+                    self.total_required_time -= current.get_total_time_left();
+                    self.statistics.push(current.finalize());
+                }
+                else if current.is_blocked() {
+                    let io_time = current.start_io();
+                    self.trace.block(self.current_time, &current);
+                    self.fairness.leave(current.get_id());
+                    self.io_device.block(self.current_time, (current, pass), io_time);
+                }
+                else if self.time_left_for_current_process == 0 {
+                    self.queue.insert((pass, current.get_id()), current);
+                }
+                else {
+                    self.current_process = Some((current, pass));
+                }
+                self.update_min_pass();
+            }
+            else {
+                let idle_time = self.io_device.limit(self.current_time, n);
+                self.waiting_time = idle_time;
+                self.working_time = 0;
+                self.total_waiting_time += self.waiting_time;
+                self.current_time += idle_time;
+                n -= idle_time;
+            }
+        }
+    }
+
+    #[inline]
+    fn get_total_working_time(&self) -> Time {
+        self.total_working_time
+    }
+
+    #[inline]
+    fn get_total_waiting_time(&self) -> Time {
+        self.total_waiting_time
+    }
+
+    #[inline]
+    fn get_working_time(&self) -> Time {
+        self.working_time
+    }
+
+    #[inline]
+    fn get_waiting_time(&self) -> Time {
+        self.waiting_time
+    }
+
+    #[inline]
+    fn get_context_switches_count(&self) -> usize {
+        self.context_switch.get_count()
+    }
+
+    #[inline]
+    fn get_context_switches_time(&self) -> Time {
+        self.context_switch.get_total_time()
+    }
+
+    #[inline]
+    fn get_io_utilization(&self) -> f64 {
+        self.io_device.get_working_time() as f64 / self.current_time.max(1) as f64
+    }
+
+    #[inline]
+    fn get_fairness_deviation(&self) -> Option<f64> {
+        Some(self.fairness.get_deviation())
+    }
+
+    #[inline]
+    fn get_processes_shares(&self) -> Vec<ProcessShare> {
+        self.fairness.get_shares().to_vec()
+    }
+
+    #[inline]
+    fn enable_trace(&mut self) {
+        self.trace.enable()
+    }
+
+    #[inline]
+    fn get_trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    #[inline]
+    fn simulate_till_end_of_every_process(&mut self) {
+        self.is_ending = true;
+        while self.is_working() {
+            self.simulate_n_ticks(self.total_required_time)
+        }
+        self.is_ending = false;
+    }
+
+    #[inline]
+    fn finalize(mut self: Box<Self>) -> Vec<cpu_access_manager::ProcessStatistics> {
+        self.simulate_till_end_of_every_process();
+        self.statistics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::invariant_tests::{check_invariants, random_workload, Run};
+    use crate::report;
+    use crate::simulator::{self, Processes, SimulationSettings};
+
+    use super::*;
+
+    // Stride of process with the most tickets allowed is still 1, more tickets would give stride of 0 and process would never pay for the processor
+    #[test]
+    fn huge_ticket_counts_keep_stride_positive() {
+        assert!(matches!(Stride::new(1, 0).unwrap().add_process(&ProcessDescription::new(5, None, 0).with_tickets(u32::MAX)), Err(SimulationError::InvalidProcess(_))));
+        assert!(matches!(Stride::new(1, 0).unwrap().add_process(&ProcessDescription::new(5, None, 0).with_tickets(STRIDE_ONE as u32 + 1)), Err(SimulationError::InvalidProcess(_))));
+        assert!(Lottery::new(1, Some(0), 0).unwrap().add_process(&ProcessDescription::new(5, None, 0).with_tickets(u32::MAX)).is_ok());
+        let workload = Processes::from(vec![(0, ProcessDescription::new(200, None, 0).with_tickets(STRIDE_ONE as u32)), (0, ProcessDescription::new(100, None, 0).with_tickets(STRIDE_ONE as u32 / 2))]);
+        let run = Run::new("Stride", 1, true, Stride::new(1, 0).unwrap(), &workload);
+        check_invariants(&run, &workload, 0);
+        for process in run.statistics.iter() {
+            assert!(process.get_completion_time().unwrap().abs_diff(300) <= 2, "process {} ended at {:?}", process.get_id(), process.get_completion_time());
+        }
+    }
+
+    #[test]
+    fn lottery_with_the_same_seed_gives_the_same_statistics() {
+        let workload = random_workload(11);
+        let simulate = |lottery_seed| {
+            let statistics = simulator::simulate_every(workload.clone(), &SimulationSettings { lottery_seed, algorithms: Some(vec!["Lottery".to_owned()]), ..SimulationSettings::default() }).unwrap().remove(0).1;
+            (report::get_metrics(&statistics), statistics.get_processes_shares().iter().map(|share| (share.get_process_id(), share.get_achieved_share())).collect::<Vec<_>>())
+        };
+        assert_eq!(simulate(Some(5)), simulate(Some(5)));
+        assert_ne!(simulate(Some(5)), simulate(Some(6)));
+    }
+
+    // Processes of every ticket count would finish together if they got exactly their share, so each is ready for the whole run
+    #[test]
+    fn lottery_share_follows_tickets() {
+        let workload = Processes::from((1..=3).map(|tickets| (0, ProcessDescription::new(1000 * tickets as Time, None, 0).with_tickets(100 * tickets))).collect::<Vec<_>>());
+        for seed in 0..5 {
+            let statistics = simulator::simulate_every(workload.clone(), &SimulationSettings { quant: 1, lottery_seed: Some(seed), algorithms: Some(vec!["Lottery".to_owned()]), ..SimulationSettings::default() }).unwrap().remove(0).1;
+            for share in statistics.get_processes_shares() {
+                assert!((share.get_requested_share() - share.get_weight() as f64 / 600f64).abs() < 0.02, "seed {}: {:?}", seed, share);
+                assert!((share.get_achieved_share() - share.get_requested_share()).abs() < 0.02, "seed {}: {:?}", seed, share);
+            }
+        }
+    }
+}
@@ -0,0 +1,145 @@
+// Schedulers selected by name at runtime, e.g. from command line, the built-in ones are registered by Registry::new
+// and schedulers defined in other crates can be registered next to them, so they are simulated and compared in the same way.
+//
+// Example:
+//   let mut registry = Registry::new();
+//   registry.register("Round robin with quant 2", |settings| Ok(Box::new(Rotating::new(2, settings.context_switch_cost))))?;
+//   let results = simulator::simulate_registered(&registry, processes, &SimulationSettings::default())?;
+
+use crate::cpu_access_manager::{CpuAccessManager, SimulationError};
+use crate::simulator::SimulationSettings;
+use crate::{cfs, deadline, fair_share, fcfs, hrrn, mlfq, multicore, periodic, predictive_sjf, priority, proportional_share, rotating, sjf};
+
+// Creates scheduler with parameters taken from settings, parameters which settings don't have can be captured by the closure
+pub type SchedulerFactory = Box<dyn Fn(&SimulationSettings) -> Result<Box<dyn CpuAccessManager>, SimulationError>>;
+
+struct Entry {
+    name: String,
+    is_quantum_based: bool, // Uses settings.quant, so it's compared across quantum values in sweeps
+    factory: SchedulerFactory,
+}
+
+pub struct Registry {
+    entries: Vec<Entry>, // In order of registration, results are reported in the same order
+}
+
+impl Registry {
+    // Every built-in scheduler
+    pub fn new() -> Self {
+        let mut ans = Self::empty();
+        ans.insert("FCFS", false, Box::new(|settings| Ok(Box::new(fcfs::FCFS::new(settings.context_switch_cost)))));
+        ans.insert("SJF", false, Box::new(|settings| Ok(Box::new(sjf::SJF::new(settings.context_switch_cost)))));
+        ans.insert("SJF with preemption", false, Box::new(|settings| Ok(Box::new(sjf::SJFWithPreemption::new(settings.context_switch_cost)))));
+        ans.insert("HRRN", false, Box::new(|settings| Ok(Box::new(hrrn::HRRN::new(settings.context_switch_cost)))));
+        ans.insert("Predictive SJF", false, Box::new(|settings| Ok(Box::new(predictive_sjf::PredictiveSJF::new(settings.prediction_alpha, settings.initial_burst_estimate, settings.context_switch_cost)?))));
+        ans.insert("Predictive SJF with preemption", false, Box::new(|settings| Ok(Box::new(predictive_sjf::PredictiveSJFWithPreemption::new(settings.prediction_alpha, settings.initial_burst_estimate, settings.context_switch_cost)?))));
+        ans.insert("Rotating", true, Box::new(|settings| match settings.quant {
+            0 => Err(SimulationError::InvalidParameter("Quant cannot be 0")),
+            quant => Ok(Box::new(rotating::Rotating::new(quant, settings.context_switch_cost))),
+        }));
+        ans.insert("Rotating with mean burst quant", false, Box::new(|settings| Ok(Box::new(rotating::AdaptiveRotating::new(rotating::QuantumRule::MeanBurst, settings.adaptive_minimum_quant, settings.adaptive_maximum_quant, settings.context_switch_cost)?))));
+        ans.insert("Rotating with median burst quant", false, Box::new(|settings| Ok(Box::new(rotating::AdaptiveRotating::new(rotating::QuantumRule::MedianBurst, settings.adaptive_minimum_quant, settings.adaptive_maximum_quant, settings.context_switch_cost)?))));
+        ans.insert("Rotating with target latency", false, Box::new(|settings| Ok(Box::new(rotating::AdaptiveRotating::new(rotating::QuantumRule::TargetLatency(settings.adaptive_target_latency), settings.adaptive_minimum_quant, settings.adaptive_maximum_quant, settings.context_switch_cost)?))));
+        ans.insert("MLFQ", false, Box::new(|settings| Ok(Box::new(mlfq::MLFQ::new(settings.mlfq_quantum_times.clone(), settings.mlfq_boost_period, settings.context_switch_cost)?))));
+        ans.insert("Priority", false, Box::new(|settings| Ok(Box::new(priority::Priority::new(settings.aging_period, settings.context_switch_cost)?))));
+        ans.insert("Priority with preemption", false, Box::new(|settings| Ok(Box::new(priority::PriorityWithPreemption::new(settings.aging_period, settings.context_switch_cost)?))));
+        ans.insert("CFS", false, Box::new(|settings| Ok(Box::new(cfs::CFS::new(settings.cfs_target_latency, settings.cfs_minimum_granularity, settings.context_switch_cost)?))));
+        ans.insert("Lottery", true, Box::new(|settings| Ok(Box::new(proportional_share::Lottery::new(settings.quant, settings.lottery_seed, settings.context_switch_cost)?))));
+        ans.insert("Stride", true, Box::new(|settings| Ok(Box::new(proportional_share::Stride::new(settings.quant, settings.context_switch_cost)?))));
+        ans.insert("Fair share", true, Box::new(|settings| Ok(Box::new(fair_share::FairShare::new(settings.quant, settings.group_shares.clone(), settings.group_policy, settings.context_switch_cost)?))));
+        ans.insert("EDF", false, Box::new(|settings| Ok(Box::new(deadline::EDF::new(settings.abandon_hopeless_processes, settings.context_switch_cost)))));
+        ans.insert("LLF", true, Box::new(|settings| Ok(Box::new(deadline::LLF::new(settings.quant, settings.abandon_hopeless_processes, settings.context_switch_cost)?))));
+        ans.insert("Rate monotonic", false, Box::new(|settings| Ok(Box::new(periodic::FixedPriority::new(periodic::PriorityAssignment::RateMonotonic, settings.context_switch_cost)))));
+        ans.insert("Deadline monotonic", false, Box::new(|settings| Ok(Box::new(periodic::FixedPriority::new(periodic::PriorityAssignment::DeadlineMonotonic, settings.context_switch_cost)))));
+        ans.insert("SMP global queue", true, Box::new(|settings| Ok(Box::new(multicore::MultiCore::new(settings.cores_count, multicore::ReadyQueue::Global, settings.quant, settings.context_switch_cost)?))));
+        ans.insert("SMP per-core queues", true, Box::new(|settings| Ok(Box::new(multicore::MultiCore::new(settings.cores_count, multicore::ReadyQueue::PerCore { balance_period: settings.load_balance_period }, settings.quant, settings.context_switch_cost)?))));
+        ans
+    }
+
+    #[inline]
+    pub fn empty() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    fn insert(&mut self, name: &str, is_quantum_based: bool, factory: SchedulerFactory) {
+        self.entries.push(Entry { name: name.to_owned(), is_quantum_based, factory });
+    }
+
+    fn register_entry(&mut self, name: &str, is_quantum_based: bool, factory: SchedulerFactory) -> Result<(), SimulationError> {
+        if name.trim().is_empty() {
+            return Err(SimulationError::InvalidParameter("Scheduler name cannot be empty"));
+        }
+        if self.find(name).is_some() {
+            return Err(SimulationError::InvalidParameter("Scheduler with this name is already registered"));
+        }
+        self.insert(name, is_quantum_based, factory);
+        Ok(())
+    }
+
+    // Names are compared case insensitively, so two schedulers can't differ only by case
+    pub fn register<F>(&mut self, name: &str, factory: F) -> Result<(), SimulationError> where F: Fn(&SimulationSettings) -> Result<Box<dyn CpuAccessManager>, SimulationError> + 'static {
+        self.register_entry(name, false, Box::new(factory))
+    }
+
+    // Scheduler which uses settings.quant, it's simulated by simulate_quantum_based and sweep_quantum too
+    pub fn register_quantum_based<F>(&mut self, name: &str, factory: F) -> Result<(), SimulationError> where F: Fn(&SimulationSettings) -> Result<Box<dyn CpuAccessManager>, SimulationError> + 'static {
+        self.register_entry(name, true, Box::new(factory))
+    }
+
+    #[inline]
+    pub fn get_names(&self) -> Vec<&str> {
+        self.entries.iter().map(|entry| entry.name.as_str()).collect()
+    }
+
+    #[inline]
+    pub fn get_quantum_based_names(&self) -> Vec<&str> {
+        self.entries.iter().filter(|entry| entry.is_quantum_based).map(|entry| entry.name.as_str()).collect()
+    }
+
+    // Registered name of scheduler given case insensitively
+    pub fn find(&self, name: &str) -> Option<&str> {
+        self.entries.iter().find(|entry| entry.name.eq_ignore_ascii_case(name.trim())).map(|entry| entry.name.as_str())
+    }
+
+    // None if there is no such scheduler, error if settings are invalid for it
+    pub fn create(&self, name: &str, settings: &SimulationSettings) -> Result<Option<Box<dyn CpuAccessManager>>, SimulationError> {
+        match self.entries.iter().find(|entry| entry.name.eq_ignore_ascii_case(name.trim())) {
+            Some(entry) => Ok(Some((entry.factory)(settings)?)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl Default for Registry {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::invariant_tests::random_workload;
+    use crate::simulator;
+
+    use super::*;
+
+    // Schedulers registered from outside are simulated after the built-in ones, names can't repeat even with different case
+    #[test]
+    fn registry_accepts_in_house_schedulers() {
+        let workload = random_workload(3);
+        let mut registry = Registry::new();
+        let context_switch_cost = 1;
+        registry.register("FCFS with fixed switch cost", move |_| Ok(Box::new(fcfs::FCFS::new(context_switch_cost)))).unwrap();
+        assert!(matches!(registry.register("fcfs", |settings| Ok(Box::new(fcfs::FCFS::new(settings.context_switch_cost)))), Err(SimulationError::InvalidParameter(_))));
+        assert!(matches!(registry.register(" ", |settings| Ok(Box::new(fcfs::FCFS::new(settings.context_switch_cost)))), Err(SimulationError::InvalidParameter(_))));
+        assert_eq!(registry.find("fcfs WITH FIXED switch COST"), Some("FCFS with fixed switch cost"));
+        assert!(registry.create("Unknown", &SimulationSettings::default()).unwrap().is_none());
+        assert!(registry.create("Rotating", &SimulationSettings { quant: 0, ..SimulationSettings::default() }).is_err());
+        let settings = SimulationSettings { algorithms: Some(vec!["FCFS with fixed switch cost".to_owned(), "FCFS".to_owned()]), context_switch_cost, ..SimulationSettings::default() };
+        let results = simulator::simulate_registered(&registry, workload, &settings).unwrap();
+        assert_eq!(results.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(), ["FCFS", "FCFS with fixed switch cost"]);
+        assert_eq!(results[0].1.get_average_waiting_time(), results[1].1.get_average_waiting_time());
+        assert_eq!(results[0].1.get_makespan(), results[1].1.get_makespan());
+    }
+}
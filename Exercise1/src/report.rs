@@ -0,0 +1,102 @@
+use std::fmt::Display;
+
+use crate::simulator::SimulationStatistics;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableFormat {
+    Csv,
+    Markdown,
+}
+
+// Metrics for which bigger value means better algorithm, for the rest smaller value is better
+const HIGHER_IS_BETTER: [&str; 8] = ["finished_processes_count", "successful_processes_count", "deadline_hit_ratio", "effective_utilization", "cpu_utilization", "io_utilization", "working_time", "throughput"];
+
+#[inline]
+pub fn is_higher_better(metric: &str) -> bool {
+    HIGHER_IS_BETTER.contains(&metric)
+}
+
+// Every statistic which is a single number, values which don't apply to the algorithm are None
+pub fn get_metrics(statistics: &SimulationStatistics) -> Vec<(&'static str, Option<f64>)> {
+    vec![
+        ("processes_count", Some(statistics.get_processes_count() as f64)),
+        ("average_waiting_time", Some(statistics.get_average_waiting_time())),
+        ("longest_waiting_time", Some(statistics.get_longest_waiting_time() as f64)),
+        ("average_call_count", Some(statistics.get_average_call_count())),
+        ("average_call_count_of_successful_processes", Some(statistics.get_average_call_count_of_successful_processes())),
+        ("average_partial_waiting_time", Some(statistics.get_average_partial_waiting_time())),
+        ("lifetime_processes_count", Some(statistics.get_lifetime_processes_count() as f64)),
+        ("finished_processes_count", Some(statistics.get_finished_processes_count() as f64)),
+        ("successful_processes_count", Some(statistics.get_successful_processes_count() as f64)),
+        ("deadline_hit_ratio", statistics.get_deadline_hit_ratio()),
+        ("context_switches_count", Some(statistics.get_context_switches_count() as f64)),
+        ("context_switches_time", Some(statistics.get_context_switches_time() as f64)),
+        ("effective_utilization", Some(statistics.get_effective_utilization())),
+        ("cpu_utilization", Some(statistics.get_cpu_utilization())),
+        ("io_utilization", Some(statistics.get_io_utilization())),
+        ("migrations_count", Some(statistics.get_migrations_count() as f64)),
+        ("prediction_error", statistics.get_prediction_error()),
+        ("fairness_deviation", statistics.get_fairness_deviation()),
+        ("abandoned_count", statistics.get_abandoned_count().map(|count| count as f64)),
+        ("waiting_time_p50", Some(statistics.get_waiting_time().get_p50() as f64)),
+        ("waiting_time_p90", Some(statistics.get_waiting_time().get_p90() as f64)),
+        ("waiting_time_p99", Some(statistics.get_waiting_time().get_p99() as f64)),
+        ("average_turnaround_time", Some(statistics.get_turnaround_time().get_average())),
+        ("turnaround_time_p50", Some(statistics.get_turnaround_time().get_p50() as f64)),
+        ("turnaround_time_p90", Some(statistics.get_turnaround_time().get_p90() as f64)),
+        ("turnaround_time_p99", Some(statistics.get_turnaround_time().get_p99() as f64)),
+        ("longest_turnaround_time", Some(statistics.get_turnaround_time().get_max() as f64)),
+        ("average_response_time", Some(statistics.get_response_time().get_average())),
+        ("response_time_p50", Some(statistics.get_response_time().get_p50() as f64)),
+        ("response_time_p90", Some(statistics.get_response_time().get_p90() as f64)),
+        ("response_time_p99", Some(statistics.get_response_time().get_p99() as f64)),
+        ("longest_response_time", Some(statistics.get_response_time().get_max() as f64)),
+        ("working_time", Some(statistics.get_working_time() as f64)),
+        ("idle_time", Some(statistics.get_idle_time() as f64)),
+        ("makespan", Some(statistics.get_makespan() as f64)),
+        ("throughput", Some(statistics.get_throughput())),
+    ]
+}
+
+// Whole numbers are printed without fraction
+#[inline]
+pub fn format_value(value: f64) -> String {
+    match value.fract() == 0f64 && value.abs() < 1e15 {
+        true => format!("{}", value as i64),
+        false => format!("{:.6}", value),
+    }
+}
+
+pub fn render_table(header: &[String], rows: &[Vec<String>], format: TableFormat) -> String {
+    let mut lines = Vec::new();
+    match format {
+        TableFormat::Csv => lines.push(header.join(",")),
+        TableFormat::Markdown => {
+            lines.push(format!("| {} |", header.join(" | ")));
+            lines.push(format!("|{}", "---|".repeat(header.len())));
+        },
+    }
+    for row in rows {
+        lines.push(match format {
+            TableFormat::Csv => row.join(","),
+            TableFormat::Markdown => format!("| {} |", row.join(" | ")),
+        });
+    }
+    lines.join("\n") + "\n"
+}
+
+// One row per key (e.g. quantum value) and algorithm, with every statistic which is a single number
+pub fn to_table<K: Display>(key_name: &str, rows: &[(K, Vec<(String, SimulationStatistics)>)], format: TableFormat) -> String {
+    let mut header = vec![key_name.to_owned(), "algorithm".to_owned()];
+    let mut table_rows = Vec::new();
+    for (key, results) in rows {
+        for (name, statistics) in results {
+            let metrics = get_metrics(statistics);
+            if header.len() == 2 {
+                header.extend(metrics.iter().map(|(metric, _)| metric.to_string()));
+            }
+            table_rows.push([key.to_string(), name.clone()].into_iter().chain(metrics.into_iter().map(|(_, value)| value.map(format_value).unwrap_or_default())).collect());
+        }
+    }
+    render_table(&header, &table_rows, format)
+}
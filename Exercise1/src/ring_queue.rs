@@ -0,0 +1,79 @@
+// Circular queue with cursor kept on contiguous ring buffer, current element is always at the front, so moving the cursor is just rotation of the buffer
+
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone)]
+pub struct RingQueue<T> {
+    buffer: VecDeque<T>,
+}
+
+impl<T> Default for RingQueue<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> RingQueue<T> {
+    #[inline]
+    pub fn new() -> Self {
+        Self { buffer: VecDeque::new() }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    // New element is placed right after the current one, the first element becomes current
+    #[inline]
+    pub fn add(&mut self, val: T) {
+        match self.buffer.is_empty() {
+            true => self.buffer.push_back(val),
+            false => self.buffer.insert(1, val),
+        }
+    }
+
+    // Removes the current element, the previous one becomes current
+    #[inline]
+    pub fn erase(&mut self) -> Option<T> {
+        let ans = self.buffer.pop_front();
+        self.prev();
+        ans
+    }
+
+    #[inline]
+    pub fn next(&mut self) {
+        if let Some(val) = self.buffer.pop_front() {
+            self.buffer.push_back(val);
+        }
+    }
+
+    #[inline]
+    pub fn prev(&mut self) {
+        if let Some(val) = self.buffer.pop_back() {
+            self.buffer.push_front(val);
+        }
+    }
+
+    #[inline]
+    pub fn get(&self) -> Option<&T> {
+        self.buffer.front()
+    }
+
+    #[inline]
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        self.buffer.front_mut()
+    }
+
+    // Every element starting from the current one
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.buffer.iter()
+    }
+}
@@ -1,105 +1,444 @@
-use crate::cpu_access_manager::{self, CpuAccessManager, Process};
-use crate::loop_list::LoopListIter;
+use crate::cpu_access_manager::{self, CpuAccessManager, Process, ProcessDescription, SimulationError, Time};
+use crate::io_device::IoDevice;
+use crate::trace::Trace;
+use crate::ring_queue::RingQueue;
 
 #[derive(Debug)]
 pub struct Rotating {
-    quantum_time: u32,
-    time_left_for_current_process: u32,
-    queue: LoopListIter<Process>,
-    working_time: u32,
-    waiting_time: u32,
-    total_working_time: u32,
-    total_waiting_time: u32,
+    quantum_time: Time,
+    time_left_for_current_process: Time,
+    queue: RingQueue<Process>,
+    is_ending: bool,
+    working_time: Time,
+    waiting_time: Time,
+    total_working_time: Time,
+    total_waiting_time: Time,
     next_id: u32,
-    current_time: u32,
+    current_time: Time,
     statistics: Vec<cpu_access_manager::ProcessStatistics>,
-    total_required_time: u32,
+    total_required_time: Time,
+    context_switch: cpu_access_manager::ContextSwitch,
+    trace: Trace,
+    io_device: IoDevice<Process>,
+    fairness: cpu_access_manager::FairnessTracker,
 }
 
 impl Rotating {
     #[inline]
-    pub fn new(quantum_time: u32) -> Self {
-        Self { quantum_time, time_left_for_current_process: quantum_time, queue: LoopListIter::new(), working_time: 0, waiting_time: 0, total_working_time: 0, total_waiting_time: 0, next_id: 0, current_time: 0, statistics: Vec::new(), total_required_time: 0 }
+    pub fn new(quantum_time: Time, context_switch_cost: Time) -> Self {
+        Self { quantum_time, time_left_for_current_process: quantum_time, queue: RingQueue::new(), is_ending: false, working_time: 0, waiting_time: 0, total_working_time: 0, total_waiting_time: 0, next_id: 0, current_time: 0, statistics: Vec::new(), total_required_time: 0, context_switch: cpu_access_manager::ContextSwitch::new(context_switch_cost), trace: Trace::new(), io_device: IoDevice::new(), fairness: cpu_access_manager::FairnessTracker::new() }
+    }
+
+    fn return_from_io(&mut self) {
+        for (mut process, time) in self.io_device.advance_to(self.current_time) {
+            process.end_io(time);
+            self.trace.io_completion(time, process.get_id());
+            self.fairness.make_ready(process.get_id(), 1);
+            self.queue.add(process);
+        }
     }
 }
 
 impl CpuAccessManager for Rotating {
-    fn add_process(&mut self, time: u32, lifetime: Option<u32>) -> u32 {
-        if time == 0 {
-            panic!("Process can't have duration time of 0");
-        }
-        self.queue.add(Process::new(self.next_id, self.current_time, time, lifetime));
+    fn add_process(&mut self, description: &ProcessDescription) -> Result<u32, SimulationError> {
+        description.validate()?;
+        let total_required_time = self.total_required_time.checked_add(description.get_total_duration()).ok_or(SimulationError::TimeOverflow)?;
+        self.queue.add(Process::new(self.next_id, self.current_time, description));
         let ans = self.next_id;
+        self.trace.arrival(self.current_time, ans);
+        self.fairness.make_ready(ans, 1);
         self.next_id += 1;
-        self.total_required_time += time;
-        ans
+        self.total_required_time = total_required_time;
+        Ok(ans)
     }
 
     fn is_working(&self) -> bool {
-        !self.queue.is_empty()
+        !self.queue.is_empty() || self.io_device.is_working()
     }
 
     fn simulate_one_tick(&mut self) {
         self.simulate_n_ticks(1)
     }
 
-    fn simulate_n_ticks(&mut self, mut n: u32) {
+    fn simulate_n_ticks(&mut self, mut n: Time) {
         while n != 0 {
-            if let Some(node) = self.queue.get() {
-                let time_for_process = n.min(self.time_left_for_current_process);
-                let worked_time = node.borrow_mut().get_mut().work_for(self.current_time, time_for_process, time_for_process == self.quantum_time);
+            self.return_from_io();
+            if self.is_ending {
+                n = n.min(self.total_required_time);
+            }
+            if let Some(current) = self.queue.get_mut() {
+                let switch_time = self.context_switch.switch_to(current.get_id(), self.io_device.limit(self.current_time, n));
+                n -= switch_time;
+                self.current_time += switch_time;
+                if self.context_switch.is_switching() {
+                    continue;
+                }
+                let time_for_process = self.io_device.limit(self.current_time, n).min(self.time_left_for_current_process);
+                let worked_time = current.work_for(self.current_time, time_for_process, self.time_left_for_current_process == self.quantum_time);
                 n -= worked_time;
                 self.time_left_for_current_process -= worked_time;
                 self.total_required_time -= worked_time;
+                self.total_working_time += worked_time;
+                self.trace.run(current.get_id(), self.current_time, worked_time);
+                self.fairness.run(current.get_id(), worked_time);
                 self.current_time += worked_time;
-                if node.borrow().get().is_finished() {
-                    drop(node);
+                if current.is_finished() {
+                    self.trace.finish(self.current_time, current);
+                    self.fairness.finish(current.get_id());
+                    self.total_required_time -= current.get_total_time_left();
                     self.statistics.push(self.queue.erase().unwrap().finalize());
                     self.time_left_for_current_process = self.quantum_time;
                     self.queue.next();
                 }
+                else if current.is_blocked() {
+                    let mut current = self.queue.erase().unwrap();
+                    let io_time = current.start_io();
+                    self.trace.block(self.current_time, &current);
+                    self.fairness.leave(current.get_id());
+                    self.io_device.block(self.current_time, current, io_time);
+                    self.time_left_for_current_process = self.quantum_time;
+                    self.queue.next();
+                }
                 if self.time_left_for_current_process == 0 {   
                     self.time_left_for_current_process = self.quantum_time;
                     self.queue.next();
                 }
             }
             else {
-                self.waiting_time = n;
+                let idle_time = self.io_device.limit(self.current_time, n);
+                self.waiting_time = idle_time;
                 self.working_time = 0;
                 self.total_waiting_time += self.waiting_time;
-                return;
+                self.current_time += idle_time;
+                n -= idle_time;
             }
         }
     }
 
     #[inline]
-    fn get_total_working_time(&self) -> u32 {
+    fn get_total_working_time(&self) -> Time {
         self.total_working_time
     }
 
     #[inline]
-    fn get_total_waiting_time(&self) -> u32 {
+    fn get_total_waiting_time(&self) -> Time {
         self.total_waiting_time
     }
 
     #[inline]
-    fn get_working_time(&self) -> u32 {
+    fn get_working_time(&self) -> Time {
         self.working_time
     }
 
     #[inline]
-    fn get_waiting_time(&self) -> u32 {
+    fn get_waiting_time(&self) -> Time {
         self.waiting_time
     }
 
+    #[inline]
+    fn get_context_switches_count(&self) -> usize {
+        self.context_switch.get_count()
+    }
+
+    #[inline]
+    fn get_context_switches_time(&self) -> Time {
+        self.context_switch.get_total_time()
+    }
+
+    #[inline]
+    fn get_io_utilization(&self) -> f64 {
+        self.io_device.get_working_time() as f64 / self.current_time.max(1) as f64
+    }
+
+    #[inline]
+    fn get_fairness_deviation(&self) -> Option<f64> {
+        Some(self.fairness.get_deviation())
+    }
+
+    #[inline]
+    fn get_processes_shares(&self) -> Vec<cpu_access_manager::ProcessShare> {
+        self.fairness.get_shares().to_vec()
+    }
+
+    #[inline]
+    fn enable_trace(&mut self) {
+        self.trace.enable()
+    }
+
+    #[inline]
+    fn get_trace(&self) -> &Trace {
+        &self.trace
+    }
+
     #[inline]
     fn simulate_till_end_of_every_process(&mut self) {
-        self.simulate_n_ticks(self.total_required_time);
+        self.is_ending = true;
+        while self.is_working() {
+            self.simulate_n_ticks(self.total_required_time)
+        }
+        self.is_ending = false;
     }
 
     #[inline]
-    fn finalize(mut self) -> Vec<cpu_access_manager::ProcessStatistics> {
+    fn finalize(mut self: Box<Self>) -> Vec<cpu_access_manager::ProcessStatistics> {
         self.simulate_till_end_of_every_process();
         self.statistics
     }
 }
+
+// Way of choosing quantum at the start of every quantum, from processes in the ready queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuantumRule {
+    MeanBurst, // Mean remaining burst
+    MedianBurst, // Median remaining burst, it isn't shifted by few very long bursts
+    TargetLatency(Time), // Every ready process gets the processor once within the latency
+}
+
+// Round robin whose quantum adapts to the ready queue, it's kept between minimum and maximum
+#[derive(Debug)]
+pub struct AdaptiveRotating {
+    rule: QuantumRule,
+    minimum_quantum_time: Time,
+    maximum_quantum_time: Time,
+    quantum_time: Time,
+    time_left_for_current_process: Time, // 0 - quantum has to be chosen before the next process runs
+    queue: RingQueue<Process>,
+    is_ending: bool,
+    working_time: Time,
+    waiting_time: Time,
+    total_working_time: Time,
+    total_waiting_time: Time,
+    next_id: u32,
+    current_time: Time,
+    statistics: Vec<cpu_access_manager::ProcessStatistics>,
+    total_required_time: Time,
+    context_switch: cpu_access_manager::ContextSwitch,
+    trace: Trace,
+    io_device: IoDevice<Process>,
+    fairness: cpu_access_manager::FairnessTracker,
+}
+
+impl AdaptiveRotating {
+    pub fn new(rule: QuantumRule, minimum_quantum_time: Time, maximum_quantum_time: Time, context_switch_cost: Time) -> Result<Self, SimulationError> {
+        if minimum_quantum_time == 0 || minimum_quantum_time > maximum_quantum_time {
+            return Err(SimulationError::InvalidParameter("Quant bounds have to be positive and minimum cannot exceed maximum"));
+        }
+        if rule == QuantumRule::TargetLatency(0) {
+            return Err(SimulationError::InvalidParameter("Target latency cannot be 0"));
+        }
+        Ok(Self { rule, minimum_quantum_time, maximum_quantum_time, quantum_time: minimum_quantum_time, time_left_for_current_process: 0, queue: RingQueue::new(), is_ending: false, working_time: 0, waiting_time: 0, total_working_time: 0, total_waiting_time: 0, next_id: 0, current_time: 0, statistics: Vec::new(), total_required_time: 0, context_switch: cpu_access_manager::ContextSwitch::new(context_switch_cost), trace: Trace::new(), io_device: IoDevice::new(), fairness: cpu_access_manager::FairnessTracker::new() })
+    }
+
+    fn return_from_io(&mut self) {
+        for (mut process, time) in self.io_device.advance_to(self.current_time) {
+            process.end_io(time);
+            self.trace.io_completion(time, process.get_id());
+            self.fairness.make_ready(process.get_id(), 1);
+            self.queue.add(process);
+        }
+    }
+
+    fn choose_quantum(&self) -> Time {
+        let mut bursts: Vec<Time> = self.queue.iter().map(Process::get_time_left).collect();
+        let quantum_time = match self.rule {
+            QuantumRule::MeanBurst => bursts.iter().map(|burst| *burst as u128).sum::<u128>().div_ceil(bursts.len().max(1) as u128).min(Time::MAX as u128) as Time,
+            QuantumRule::MedianBurst => {
+                bursts.sort_unstable();
+                bursts.get(bursts.len().saturating_sub(1) / 2).copied().unwrap_or(0)
+            },
+            QuantumRule::TargetLatency(latency) => latency.div_ceil(bursts.len().max(1) as Time),
+        };
+        quantum_time.clamp(self.minimum_quantum_time, self.maximum_quantum_time)
+    }
+
+    #[inline]
+    pub fn get_quantum_time(&self) -> Time {
+        self.quantum_time
+    }
+}
+
+impl CpuAccessManager for AdaptiveRotating {
+    fn add_process(&mut self, description: &ProcessDescription) -> Result<u32, SimulationError> {
+        description.validate()?;
+        let total_required_time = self.total_required_time.checked_add(description.get_total_duration()).ok_or(SimulationError::TimeOverflow)?;
+        self.queue.add(Process::new(self.next_id, self.current_time, description));
+        let ans = self.next_id;
+        self.trace.arrival(self.current_time, ans);
+        self.fairness.make_ready(ans, 1);
+        self.next_id += 1;
+        self.total_required_time = total_required_time;
+        Ok(ans)
+    }
+
+    fn is_working(&self) -> bool {
+        !self.queue.is_empty() || self.io_device.is_working()
+    }
+
+    fn simulate_one_tick(&mut self) {
+        self.simulate_n_ticks(1)
+    }
+
+    fn simulate_n_ticks(&mut self, mut n: Time) {
+        while n != 0 {
+            self.return_from_io();
+            if self.is_ending {
+                n = n.min(self.total_required_time);
+            }
+            if self.time_left_for_current_process == 0 && !self.queue.is_empty() {
+                self.quantum_time = self.choose_quantum();
+                self.time_left_for_current_process = self.quantum_time;
+            }
+            if let Some(current) = self.queue.get_mut() {
+                let switch_time = self.context_switch.switch_to(current.get_id(), self.io_device.limit(self.current_time, n));
+                n -= switch_time;
+                self.current_time += switch_time;
+                if self.context_switch.is_switching() {
+                    continue;
+                }
+                let time_for_process = self.io_device.limit(self.current_time, n).min(self.time_left_for_current_process);
+                let worked_time = current.work_for(self.current_time, time_for_process, self.time_left_for_current_process == self.quantum_time);
+                n -= worked_time;
+                self.time_left_for_current_process -= worked_time;
+                self.total_required_time -= worked_time;
+                self.total_working_time += worked_time;
+                self.trace.run(current.get_id(), self.current_time, worked_time);
+                self.fairness.run(current.get_id(), worked_time);
+                self.current_time += worked_time;
+                if current.is_finished() {
+                    self.trace.finish(self.current_time, current);
+                    self.fairness.finish(current.get_id());
+                    self.total_required_time -= current.get_total_time_left();
+                    self.statistics.push(self.queue.erase().unwrap().finalize());
+                    self.time_left_for_current_process = 0;
+                    self.queue.next();
+                }
+                else if current.is_blocked() {
+                    let mut current = self.queue.erase().unwrap();
+                    let io_time = current.start_io();
+                    self.trace.block(self.current_time, &current);
+                    self.fairness.leave(current.get_id());
+                    self.io_device.block(self.current_time, current, io_time);
+                    self.time_left_for_current_process = 0;
+                    self.queue.next();
+                }
+                else if self.time_left_for_current_process == 0 {
+                    self.queue.next();
+                }
+            }
+            else {
+                let idle_time = self.io_device.limit(self.current_time, n);
+                self.waiting_time = idle_time;
+                self.working_time = 0;
+                self.total_waiting_time += self.waiting_time;
+                self.current_time += idle_time;
+                n -= idle_time;
+            }
+        }
+    }
+
+    #[inline]
+    fn get_total_working_time(&self) -> Time {
+        self.total_working_time
+    }
+
+    #[inline]
+    fn get_total_waiting_time(&self) -> Time {
+        self.total_waiting_time
+    }
+
+    #[inline]
+    fn get_working_time(&self) -> Time {
+        self.working_time
+    }
+
+    #[inline]
+    fn get_waiting_time(&self) -> Time {
+        self.waiting_time
+    }
+
+    #[inline]
+    fn get_context_switches_count(&self) -> usize {
+        self.context_switch.get_count()
+    }
+
+    #[inline]
+    fn get_context_switches_time(&self) -> Time {
+        self.context_switch.get_total_time()
+    }
+
+    #[inline]
+    fn get_io_utilization(&self) -> f64 {
+        self.io_device.get_working_time() as f64 / self.current_time.max(1) as f64
+    }
+
+    #[inline]
+    fn get_fairness_deviation(&self) -> Option<f64> {
+        Some(self.fairness.get_deviation())
+    }
+
+    #[inline]
+    fn get_processes_shares(&self) -> Vec<cpu_access_manager::ProcessShare> {
+        self.fairness.get_shares().to_vec()
+    }
+
+    #[inline]
+    fn enable_trace(&mut self) {
+        self.trace.enable()
+    }
+
+    #[inline]
+    fn get_trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    #[inline]
+    fn simulate_till_end_of_every_process(&mut self) {
+        self.is_ending = true;
+        while self.is_working() {
+            self.simulate_n_ticks(self.total_required_time)
+        }
+        self.is_ending = false;
+    }
+
+    #[inline]
+    fn finalize(mut self: Box<Self>) -> Vec<cpu_access_manager::ProcessStatistics> {
+        self.simulate_till_end_of_every_process();
+        self.statistics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::invariant_tests::{random_workload, Run};
+
+    use super::*;
+
+    // Adaptive quantum which can't leave its bounds is fixed one, so every rule has to reproduce plain round robin
+    #[test]
+    fn adaptive_round_robin_with_fixed_quantum_matches_rotating() {
+        for seed in 0..30 {
+            let workload = random_workload(seed);
+            let expected = Run::new("Rotating", 1, true, Rotating::new(6, 1), &workload);
+            for rule in [QuantumRule::MeanBurst, QuantumRule::MedianBurst, QuantumRule::TargetLatency(40)] {
+                let run = Run::new("Adaptive rotating", 1, true, AdaptiveRotating::new(rule, 6, 6, 1).unwrap(), &workload);
+                for (process, expected_process) in run.statistics.iter().zip(expected.statistics.iter()) {
+                    assert_eq!((process.get_id(), process.get_completion_time(), process.is_successful()), (expected_process.get_id(), expected_process.get_completion_time(), expected_process.is_successful()), "{:?}: P{} differs", rule, process.get_id());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn adaptive_quantum_follows_ready_queue() {
+        for (rule, bursts, expected) in [(QuantumRule::MeanBurst, [1, 5, 200], 69), (QuantumRule::MedianBurst, [1, 5, 200], 5), (QuantumRule::MedianBurst, [300, 400, 500], 100), (QuantumRule::TargetLatency(30), [100, 100, 100], 10), (QuantumRule::TargetLatency(300), [1, 5, 200], 100)] {
+            let mut cpu = AdaptiveRotating::new(rule, 2, 100, 0).unwrap();
+            for burst in bursts {
+                cpu.add_process(&ProcessDescription::new(burst, None, 0)).unwrap();
+            }
+            cpu.simulate_one_tick();
+            assert_eq!(cpu.get_quantum_time(), expected, "{:?} of {:?}", rule, bursts);
+        }
+        assert!(matches!(AdaptiveRotating::new(QuantumRule::MeanBurst, 5, 4, 0), Err(SimulationError::InvalidParameter(_))));
+        assert!(matches!(AdaptiveRotating::new(QuantumRule::TargetLatency(0), 1, 4, 0), Err(SimulationError::InvalidParameter(_))));
+    }
+}
@@ -0,0 +1,238 @@
+use crate::cpu_access_manager::{CpuAccessManager, SimulationError, Time};
+use crate::periodic::{self, FixedPriority, PeriodicTask, PriorityAssignment};
+use crate::report::{self, TableFormat};
+
+// Utilization below which any set of that many tasks with deadlines equal to periods is schedulable by rate monotonic (Liu & Layland)
+pub fn get_liu_layland_bound(tasks_count: usize) -> f64 {
+    match tasks_count {
+        0 => 1f64,
+        _ => tasks_count as f64 * (2f64.powf(1f64 / tasks_count as f64) - 1f64),
+    }
+}
+
+#[inline]
+pub fn get_total_utilization(tasks: &[PeriodicTask]) -> f64 {
+    tasks.iter().map(PeriodicTask::get_utilization).sum()
+}
+
+// Exact worst case response time of every task, released together with every higher priority task, None if it can miss its deadline
+pub fn get_response_times(tasks: &[PeriodicTask], assignment: PriorityAssignment) -> Result<Vec<Option<Time>>, SimulationError> {
+    for task in tasks {
+        task.validate()?;
+    }
+    let order = assignment.get_priority_order(tasks);
+    let mut ans = vec![None; tasks.len()];
+    for (position, index) in order.iter().enumerate() {
+        let task = tasks[*index];
+        let mut response_time = task.get_wcet();
+        // Interference of higher priority tasks only grows with response time, so iteration ends at the fixed point or after the deadline
+        ans[*index] = loop {
+            match order[..position].iter().try_fold(task.get_wcet(), |sum, other| sum.checked_add(response_time.div_ceil(tasks[*other].get_period()).checked_mul(tasks[*other].get_wcet())?)) {
+                Some(next) if next > task.get_relative_deadline() => break None,
+                Some(next) if next == response_time => break Some(next),
+                Some(next) => response_time = next,
+                None => break None,
+            }
+        };
+    }
+    Ok(ans)
+}
+
+// Prediction of the analysis and outcome of the simulation for single task
+#[derive(Debug, Clone)]
+pub struct TaskAnalysis {
+    task: PeriodicTask,
+    priority_rank: usize, // 0 - the highest priority
+    predicted_response_time: Option<Time>,
+    observed_response_time: Option<Time>,
+    jobs_count: usize,
+    missed_deadlines: usize,
+}
+
+impl TaskAnalysis {
+    #[inline]
+    pub fn get_task(&self) -> &PeriodicTask {
+        &self.task
+    }
+
+    #[inline]
+    pub fn get_priority_rank(&self) -> usize {
+        self.priority_rank
+    }
+
+    #[inline]
+    pub fn get_predicted_response_time(&self) -> Option<Time> {
+        self.predicted_response_time
+    }
+
+    // The longest response time of job which met its deadline
+    #[inline]
+    pub fn get_observed_response_time(&self) -> Option<Time> {
+        self.observed_response_time
+    }
+
+    #[inline]
+    pub fn get_jobs_count(&self) -> usize {
+        self.jobs_count
+    }
+
+    #[inline]
+    pub fn get_missed_deadlines(&self) -> usize {
+        self.missed_deadlines
+    }
+
+    // Task predicted to be schedulable has to meet every deadline without exceeding the bound, the other one has to miss some deadline
+    #[inline]
+    pub fn is_prediction_confirmed(&self) -> bool {
+        match self.predicted_response_time {
+            Some(predicted) => self.missed_deadlines == 0 && self.observed_response_time.is_none_or(|observed| observed <= predicted),
+            None => self.missed_deadlines != 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SchedulabilityReport {
+    assignment: PriorityAssignment,
+    horizon: Time,
+    utilization: f64,
+    liu_layland_bound: f64,
+    tasks: Vec<TaskAnalysis>,
+}
+
+impl SchedulabilityReport {
+    #[inline]
+    pub fn get_assignment(&self) -> PriorityAssignment {
+        self.assignment
+    }
+
+    #[inline]
+    pub fn get_horizon(&self) -> Time {
+        self.horizon
+    }
+
+    #[inline]
+    pub fn get_utilization(&self) -> f64 {
+        self.utilization
+    }
+
+    #[inline]
+    pub fn get_liu_layland_bound(&self) -> f64 {
+        self.liu_layland_bound
+    }
+
+    #[inline]
+    pub fn get_tasks(&self) -> &[TaskAnalysis] {
+        &self.tasks
+    }
+
+    // Sufficient test, it applies only when every deadline equals period, then both assignments give the same priorities
+    pub fn is_schedulable_by_utilization(&self) -> Option<bool> {
+        match self.tasks.iter().all(|analysis| analysis.task.get_relative_deadline() == analysis.task.get_period()) {
+            true => Some(self.utilization <= self.liu_layland_bound),
+            false => None,
+        }
+    }
+
+    #[inline]
+    pub fn is_schedulable_by_response_time(&self) -> bool {
+        self.tasks.iter().all(|analysis| analysis.predicted_response_time.is_some())
+    }
+
+    // Killed jobs don't interfere anymore, so prediction for tasks below the one missing deadlines may be pessimistic and not confirmed
+    #[inline]
+    pub fn is_prediction_confirmed(&self) -> bool {
+        self.tasks.iter().all(TaskAnalysis::is_prediction_confirmed)
+    }
+
+    pub fn to_table(&self, format: TableFormat) -> String {
+        let header: Vec<String> = ["task", "period", "wcet", "relative_deadline", "utilization", "priority_rank", "predicted_response_time", "observed_response_time", "jobs", "missed_deadlines", "confirmed"].into_iter().map(str::to_owned).collect();
+        let rows: Vec<Vec<String>> = self.tasks.iter().enumerate().map(|(index, analysis)| vec![index.to_string(), analysis.task.get_period().to_string(), analysis.task.get_wcet().to_string(), analysis.task.get_relative_deadline().to_string(), report::format_value(analysis.task.get_utilization()), analysis.priority_rank.to_string(), analysis.predicted_response_time.map(|time| time.to_string()).unwrap_or_default(), analysis.observed_response_time.map(|time| time.to_string()).unwrap_or_default(), analysis.jobs_count.to_string(), analysis.missed_deadlines.to_string(), analysis.is_prediction_confirmed().to_string()]).collect();
+        report::render_table(&header, &rows, format)
+    }
+}
+
+// Simulates jobs released before the horizon without context switch cost, one hyperperiod is enough to see every possible job
+pub fn analyze(tasks: &[PeriodicTask], assignment: PriorityAssignment, horizon: Time) -> Result<SchedulabilityReport, SimulationError> {
+    let response_times = get_response_times(tasks, assignment)?;
+    let mut priority_ranks = vec![0; tasks.len()];
+    for (rank, index) in assignment.get_priority_order(tasks).into_iter().enumerate() {
+        priority_ranks[index] = rank;
+    }
+    let mut analyses: Vec<TaskAnalysis> = tasks.iter().zip(response_times).zip(priority_ranks).map(|((task, predicted_response_time), priority_rank)| TaskAnalysis { task: *task, priority_rank, predicted_response_time, observed_response_time: None, jobs_count: 0, missed_deadlines: 0 }).collect();
+    let mut cpu = FixedPriority::new(assignment, 0);
+    for (wait, description) in periodic::generate_jobs(tasks, horizon)?.iter() {
+        cpu.simulate_n_ticks(*wait);
+        cpu.add_process(description)?;
+    }
+    for job in Box::new(cpu).finalize() {
+        let analysis = &mut analyses[job.get_priority() as usize];
+        analysis.jobs_count += 1;
+        match job.is_successful() {
+            Some(true) => analysis.observed_response_time = analysis.observed_response_time.max(job.get_turnaround_time()),
+            _ => analysis.missed_deadlines += 1,
+        }
+    }
+    Ok(SchedulabilityReport { assignment, horizon, utilization: get_total_utilization(tasks), liu_layland_bound: get_liu_layland_bound(tasks.len()), tasks: analyses })
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+
+    use super::*;
+
+    // With every task released at 0 the first jobs meet the worst case, so response times of schedulable tasks are observed exactly
+    fn check_response_time_analysis(tasks: &[PeriodicTask], assignment: PriorityAssignment) {
+        let report = analyze(tasks, assignment, periodic::get_hyperperiod(tasks).unwrap()).unwrap();
+        if report.is_schedulable_by_utilization() == Some(true) {
+            assert!(report.is_schedulable_by_response_time(), "{:?}: utilization bound passed but response time analysis failed", tasks);
+        }
+        for index in assignment.get_priority_order(tasks) {
+            let analysis = &report.get_tasks()[index];
+            match analysis.get_predicted_response_time() {
+                Some(predicted) => {
+                    assert_eq!(analysis.get_missed_deadlines(), 0, "{:?} {:?}: T{} missed deadline", tasks, assignment, index);
+                    assert_eq!(analysis.get_observed_response_time(), Some(predicted), "{:?} {:?}: T{} response time differs", tasks, assignment, index);
+                },
+                // Missing jobs are killed, so analysis of the tasks below can't be compared with simulation
+                None => {
+                    assert_ne!(analysis.get_missed_deadlines(), 0, "{:?} {:?}: T{} didn't miss deadline", tasks, assignment, index);
+                    break;
+                },
+            }
+        }
+    }
+
+    #[test]
+    fn response_time_analysis_matches_simulation() {
+        let tasks = [PeriodicTask::new(4, 1, 4), PeriodicTask::new(5, 2, 5), PeriodicTask::new(20, 5, 20)];
+        assert_eq!(get_response_times(&tasks, PriorityAssignment::RateMonotonic).unwrap(), vec![Some(1), Some(3), Some(15)]);
+        let report = analyze(&tasks, PriorityAssignment::RateMonotonic, 20).unwrap();
+        assert_eq!(report.is_schedulable_by_utilization(), Some(false));
+        assert!(report.is_schedulable_by_response_time() && report.is_prediction_confirmed());
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..300 {
+            let tasks: Vec<PeriodicTask> = (0..rng.gen_range(1..6)).map(|_| {
+                let period = [4, 5, 8, 10, 16, 20, 40][rng.gen_range(0..7)];
+                let wcet = rng.gen_range(1..=period / 2);
+                PeriodicTask::new(period, wcet, rng.gen_range(wcet..=period))
+            }).collect();
+            check_response_time_analysis(&tasks, PriorityAssignment::RateMonotonic);
+            check_response_time_analysis(&tasks, PriorityAssignment::DeadlineMonotonic);
+        }
+    }
+
+    #[test]
+    fn deadline_monotonic_schedules_what_rate_monotonic_cannot() {
+        let tasks = [PeriodicTask::new(10, 3, 10), PeriodicTask::new(20, 4, 5)];
+        assert!(!analyze(&tasks, PriorityAssignment::RateMonotonic, 20).unwrap().is_schedulable_by_response_time());
+        let report = analyze(&tasks, PriorityAssignment::DeadlineMonotonic, 20).unwrap();
+        assert!(report.is_schedulable_by_response_time() && report.is_prediction_confirmed());
+        assert_eq!(report.is_schedulable_by_utilization(), None);
+        assert!((get_liu_layland_bound(2) - 0.828427).abs() < 1e-6);
+        assert!(matches!(periodic::generate_jobs(&[PeriodicTask::new(10, 3, 11)], 20), Err(SimulationError::InvalidProcess(_))));
+        assert_eq!(periodic::get_hyperperiod(&[PeriodicTask::new(Time::MAX, 1, 1), PeriodicTask::new(2, 1, 1)]), Err(SimulationError::TimeOverflow));
+    }
+}
@@ -1,13 +1,15 @@
-use crate::{cpu_access_manager::CpuAccessManager, fcfs, rotating, sjf};
+use std::collections::BTreeMap;
+
+use crate::{cpu_access_manager::{CpuAccessManager, ProcessDescription, ProcessShare, SimulationError, Time}, fair_share, registry::Registry, trace::Trace};
 
 #[derive(Debug, Clone)]
 pub struct Processes {
-    process_list: std::collections::LinkedList<(u32, u32, Option<u32>)>,
+    process_list: std::collections::LinkedList<(Time, ProcessDescription)>,
 }
 
 impl Processes {
     // #[inline]
-    // fn next_duration(&mut self) -> Option<u32> {
+    // fn next_duration(&mut self) -> Option<Time> {
     //     match self.process_list.pop_front() {
     //         Some((_, next)) => Some(next),
     //         None => None,
@@ -15,7 +17,7 @@ impl Processes {
     // }
 
     // #[inline]
-    // fn next_wait(&self) -> Option<u32> {
+    // fn next_wait(&self) -> Option<Time> {
     //     match self.process_list.front() {
     //         Some((next, _)) => Some(*next),
     //         None => None,
@@ -23,42 +25,186 @@ impl Processes {
     // }
 
     #[inline]
-    #[allow(dead_code)]
     pub fn len(&self) -> usize {
         self.process_list.len()
     }
 
     #[inline]
-    fn next(&mut self) -> Option<(u32, u32, Option<u32>)> {
+    pub fn is_empty(&self) -> bool {
+        self.process_list.is_empty()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &(Time, ProcessDescription)> {
+        self.process_list.iter()
+    }
+
+    #[inline]
+    fn next(&mut self) -> Option<(Time, ProcessDescription)> {
         self.process_list.pop_front()
     }
 }
 
-impl From<Vec<(u32, u32, Option<u32>)>> for Processes {
-    fn from(value: Vec<(u32, u32, Option<u32>)>) -> Self {
-        let mut list = std::collections::LinkedList::new();
-        for val in value {
-            list.push_back(val);
-        }
-        Self { process_list: list }
+impl From<Vec<(Time, Time, Option<Time>)>> for Processes {
+    fn from(value: Vec<(Time, Time, Option<Time>)>) -> Self {
+        Self::from(value.into_iter().map(|(wait, duration, lifetime)| (wait, duration, lifetime, 0)).collect::<Vec<_>>())
+    }
+}
+
+impl From<Vec<(Time, Time, Option<Time>, u32)>> for Processes {
+    fn from(value: Vec<(Time, Time, Option<Time>, u32)>) -> Self {
+        Self::from(value.into_iter().map(|(wait, duration, lifetime, priority)| (wait, ProcessDescription::new(duration, lifetime, priority))).collect::<Vec<_>>())
     }
 }
 
-struct Simulation<T> where T : CpuAccessManager {
-    cpu: T,
+impl From<Vec<(Time, ProcessDescription)>> for Processes {
+    fn from(value: Vec<(Time, ProcessDescription)>) -> Self {
+        Self { process_list: value.into_iter().collect() }
+    }
+}
+
+// Single scheduler on single workload, any scheduler can be simulated, also the ones defined outside of this crate
+pub struct Simulation {
+    cpu: Box<dyn CpuAccessManager>,
     process_list: Processes,
 }
 
 pub struct SimulationStatistics {
     average_waiting_time: f64,
     processes_count: usize,
-    longest_waiting_time: u32,
+    longest_waiting_time: Time,
     average_call_count: f64,
     average_partial_waiting_time: f64,
     processes_with_lifetime: usize,
     finished_processes: usize,
     successful_processes: usize,
+    successful_lifetime_processes: usize,
     average_call_count_of_successful_processes: f64,
+    priority_classes: BTreeMap<u32, PriorityClassStatistics>,
+    groups: BTreeMap<u32, GroupStatistics>,
+    context_switches_count: usize,
+    context_switches_time: Time,
+    working_time: Time,
+    trace: Option<Trace>,
+    cores_utilization: Vec<f64>,
+    migrations_count: usize,
+    io_utilization: f64,
+    prediction_error: Option<f64>,
+    fairness_deviation: Option<f64>,
+    processes_shares: Vec<ProcessShare>,
+    abandoned_count: Option<usize>,
+    waiting_time: TimeStatistics,
+    turnaround_time: TimeStatistics,
+    response_time: TimeStatistics,
+    idle_time: Time,
+    makespan: Time,
+}
+
+// Average, percentiles (nearest rank) and maximum of some time measured for every process
+#[derive(Debug, Clone, Default)]
+pub struct TimeStatistics {
+    average: f64,
+    p50: Time,
+    p90: Time,
+    p99: Time,
+    max: Time,
+}
+
+impl TimeStatistics {
+    fn new(mut values: Vec<Time>) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+        values.sort_unstable();
+        let percentile = |percent: usize| values[(values.len() * percent).div_ceil(100).max(1) - 1];
+        Self { average: values.iter().map(|value| *value as f64).sum::<f64>() / values.len() as f64, p50: percentile(50), p90: percentile(90), p99: percentile(99), max: *values.last().unwrap() }
+    }
+
+    #[inline]
+    pub fn get_average(&self) -> f64 {
+        self.average
+    }
+
+    #[inline]
+    pub fn get_p50(&self) -> Time {
+        self.p50
+    }
+
+    #[inline]
+    pub fn get_p90(&self) -> Time {
+        self.p90
+    }
+
+    #[inline]
+    pub fn get_p99(&self) -> Time {
+        self.p99
+    }
+
+    #[inline]
+    pub fn get_max(&self) -> Time {
+        self.max
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PriorityClassStatistics {
+    processes_count: usize,
+    average_waiting_time: f64,
+    longest_waiting_time: Time,
+}
+
+impl PriorityClassStatistics {
+    #[inline]
+    pub fn get_processes_count(&self) -> usize {
+        self.processes_count
+    }
+
+    #[inline]
+    pub fn get_average_waiting_time(&self) -> f64 {
+        self.average_waiting_time
+    }
+
+    #[inline]
+    pub fn get_longest_waiting_time(&self) -> Time {
+        self.longest_waiting_time
+    }
+}
+
+// Processes of the same owner, processor share is part of the whole processor working time
+#[derive(Debug, Clone, Default)]
+pub struct GroupStatistics {
+    processes_count: usize,
+    processor_time: Time,
+    processor_share: f64,
+    average_waiting_time: f64,
+    longest_waiting_time: Time,
+}
+
+impl GroupStatistics {
+    #[inline]
+    pub fn get_processes_count(&self) -> usize {
+        self.processes_count
+    }
+
+    #[inline]
+    pub fn get_processor_time(&self) -> Time {
+        self.processor_time
+    }
+
+    #[inline]
+    pub fn get_processor_share(&self) -> f64 {
+        self.processor_share
+    }
+
+    #[inline]
+    pub fn get_average_waiting_time(&self) -> f64 {
+        self.average_waiting_time
+    }
+
+    #[inline]
+    pub fn get_longest_waiting_time(&self) -> Time {
+        self.longest_waiting_time
+    }
 }
 
 impl SimulationStatistics {
@@ -73,7 +219,7 @@ impl SimulationStatistics {
     }
 
     #[inline]
-    pub fn get_longest_waiting_time(&self) -> u32 {
+    pub fn get_longest_waiting_time(&self) -> Time {
         self.longest_waiting_time
     }
 
@@ -93,7 +239,6 @@ impl SimulationStatistics {
     }
 
     #[inline]
-    #[allow(dead_code)]
     pub fn get_finished_processes_count(&self) -> usize {
         self.finished_processes
     }
@@ -107,18 +252,164 @@ impl SimulationStatistics {
     pub fn get_average_call_count_of_successful_processes(&self) ->f64 {
         self.average_call_count_of_successful_processes
     }
+
+    #[inline]
+    pub fn get_waiting_time(&self) -> &TimeStatistics {
+        &self.waiting_time
+    }
+
+    #[inline]
+    pub fn get_turnaround_time(&self) -> &TimeStatistics {
+        &self.turnaround_time
+    }
+
+    // Only processes which got processor at least once are included
+    #[inline]
+    pub fn get_response_time(&self) -> &TimeStatistics {
+        &self.response_time
+    }
+
+    #[inline]
+    pub fn get_working_time(&self) -> Time {
+        self.working_time
+    }
+
+    // Time when processor had nothing to do, for many cores time when every core had nothing to do
+    #[inline]
+    pub fn get_idle_time(&self) -> Time {
+        self.idle_time
+    }
+
+    // Time from start of simulation till the last process ended
+    #[inline]
+    pub fn get_makespan(&self) -> Time {
+        self.makespan
+    }
+
+    // Finished processes per tick
+    #[inline]
+    pub fn get_throughput(&self) -> f64 {
+        self.finished_processes as f64 / self.makespan.max(1) as f64
+    }
+
+    // Processes with lifetime which ended before their deadlines
+    #[inline]
+    pub fn get_successful_lifetime_processes_count(&self) -> usize {
+        self.successful_lifetime_processes
+    }
+
+    // Part of processes with lifetime which ended before their deadlines, None if no process has lifetime
+    #[inline]
+    pub fn get_deadline_hit_ratio(&self) -> Option<f64> {
+        match self.processes_with_lifetime {
+            0 => None,
+            processes_with_lifetime => Some(self.successful_lifetime_processes as f64 / processes_with_lifetime as f64),
+        }
+    }
+
+    #[inline]
+    pub fn get_abandoned_count(&self) -> Option<usize> {
+        self.abandoned_count
+    }
+
+    #[inline]
+    pub fn get_context_switches_count(&self) -> usize {
+        self.context_switches_count
+    }
+
+    #[inline]
+    pub fn get_context_switches_time(&self) -> Time {
+        self.context_switches_time
+    }
+
+    // Part of busy processor time which was spent on processes, not on switching between them
+    #[inline]
+    pub fn get_effective_utilization(&self) -> f64 {
+        self.working_time as f64 / (self.working_time + self.context_switches_time).max(1) as f64
+    }
+
+    #[inline]
+    pub fn get_cores_utilization(&self) -> &[f64] {
+        &self.cores_utilization
+    }
+
+    #[inline]
+    pub fn get_cpu_utilization(&self) -> f64 {
+        self.cores_utilization.iter().sum::<f64>() / self.cores_utilization.len().max(1) as f64
+    }
+
+    #[inline]
+    pub fn get_io_utilization(&self) -> f64 {
+        self.io_utilization
+    }
+
+    #[inline]
+    pub fn get_migrations_count(&self) -> usize {
+        self.migrations_count
+    }
+
+    #[inline]
+    pub fn get_prediction_error(&self) -> Option<f64> {
+        self.prediction_error
+    }
+
+    #[inline]
+    pub fn get_fairness_deviation(&self) -> Option<f64> {
+        self.fairness_deviation
+    }
+
+    #[inline]
+    pub fn get_processes_shares(&self) -> &[ProcessShare] {
+        &self.processes_shares
+    }
+
+    #[inline]
+    pub fn get_trace(&self) -> Option<&Trace> {
+        self.trace.as_ref()
+    }
+
+    #[inline]
+    pub fn get_priority_classes(&self) -> &BTreeMap<u32, PriorityClassStatistics> {
+        &self.priority_classes
+    }
+
+    #[inline]
+    pub fn get_groups(&self) -> &BTreeMap<u32, GroupStatistics> {
+        &self.groups
+    }
 }
 
-impl<T> Simulation<T> where T : CpuAccessManager {
-    pub fn new(cpu: T, process_list: Processes) -> Self {
+impl Simulation {
+    pub fn new(mut cpu: Box<dyn CpuAccessManager>, process_list: Processes, record_trace: bool) -> Self {
+        if record_trace {
+            cpu.enable_trace();
+        }
         Self { cpu, process_list }
     }
 
-    pub fn simulate(mut self) -> SimulationStatistics {
-        while let Some((time_to_wait, duration, lifetime)) = self.process_list.next() {
+    // Whole workload has to fit in the time range before anything is simulated, so the schedulers can't overflow
+    pub fn simulate(mut self) -> Result<SimulationStatistics, SimulationError> {
+        self.process_list.iter().try_fold(0 as Time, |horizon, (time_to_wait, description)| horizon.checked_add(*time_to_wait)?.checked_add(description.get_checked_total_time()?)).ok_or(SimulationError::TimeOverflow)?;
+        while let Some((time_to_wait, description)) = self.process_list.next() {
             self.cpu.simulate_n_ticks(time_to_wait);
-            let _ = self.cpu.add_process(duration, lifetime);
+            self.cpu.add_process(&description)?;
         }
+        self.cpu.simulate_till_end_of_every_process();
+        let context_switches_count = self.cpu.get_context_switches_count();
+        let context_switches_time = self.cpu.get_context_switches_time();
+        let working_time = self.cpu.get_total_working_time();
+        let idle_time = self.cpu.get_total_waiting_time();
+        let cores_utilization = self.cpu.get_cores_utilization();
+        let migrations_count = self.cpu.get_migrations_count();
+        let io_utilization = self.cpu.get_io_utilization();
+        let prediction_error = self.cpu.get_prediction_error();
+        let fairness_deviation = self.cpu.get_fairness_deviation();
+        let processes_shares = self.cpu.get_processes_shares();
+        let abandoned_count = self.cpu.get_abandoned_count();
+        let trace = match self.cpu.get_trace().is_enabled() {
+            true => Some(self.cpu.get_trace().clone()),
+            false => None,
+        };
         let mut average_waiting_time = 0f64;
         let processes_statistics = self.cpu.finalize();
         let processes_count = processes_statistics.len();
@@ -128,17 +419,38 @@ impl<T> Simulation<T> where T : CpuAccessManager {
         let mut processes_with_lifetime = 0;
         let mut finished_processes = 0;
         let mut successful_processes = 0;
+        let mut successful_lifetime_processes = 0;
         let mut call_count_of_successful_processes = 0f64;
+        let mut priority_classes = BTreeMap::<u32, PriorityClassStatistics>::new();
+        let mut groups = BTreeMap::<u32, GroupStatistics>::new();
+        let mut waiting_times = Vec::with_capacity(processes_count);
+        let mut turnaround_times = Vec::with_capacity(processes_count);
+        let mut response_times = Vec::with_capacity(processes_count);
+        let mut makespan = 0;
         for process in processes_statistics {
-            let waiting_time = process.get_completion_time().unwrap() + process.get_time_left() - process.get_creation_time() - process.get_initial_time();
+            let waiting_time = process.get_waiting_time().ok_or(SimulationError::TimeOverflow)?;
+            waiting_times.push(waiting_time);
+            turnaround_times.push(process.get_turnaround_time().ok_or(SimulationError::TimeOverflow)?);
+            response_times.extend(process.get_response_time());
+            makespan = makespan.max(process.get_completion_time().unwrap());
             average_waiting_time += waiting_time as f64;
             longest_waiting_time = longest_waiting_time.max(waiting_time);
+            let priority_class = priority_classes.entry(process.get_priority()).or_default();
+            priority_class.processes_count += 1;
+            priority_class.average_waiting_time += waiting_time as f64;
+            priority_class.longest_waiting_time = priority_class.longest_waiting_time.max(waiting_time);
+            let group = groups.entry(process.get_group()).or_default();
+            group.processes_count += 1;
+            group.processor_time += process.get_executed_time().ok_or(SimulationError::TimeOverflow)?;
+            group.average_waiting_time += waiting_time as f64;
+            group.longest_waiting_time = group.longest_waiting_time.max(waiting_time);
             average_call_count += process.get_call_count() as f64;
             average_partial_waiting_time += process.get_partial_waiting_time();
             processes_with_lifetime += process.has_lifetime() as usize;
             if let Some(successful) = process.is_successful() {
                 finished_processes += 1;
                 successful_processes += successful as usize;
+                successful_lifetime_processes += (successful && process.has_lifetime()) as usize;
                 call_count_of_successful_processes += (process.get_call_count() as f64) * (successful as i32 as f64);
             }
             // println!("Id: {}", process.get_id());
@@ -149,18 +461,117 @@ impl<T> Simulation<T> where T : CpuAccessManager {
             // println!("Count: {}", process.get_count());
         }
         let avg_div = processes_count.max(1) as f64;
-        SimulationStatistics { average_waiting_time: average_waiting_time / avg_div, processes_count, longest_waiting_time, average_call_count: average_call_count / avg_div, average_partial_waiting_time: average_partial_waiting_time / avg_div, processes_with_lifetime, finished_processes, successful_processes, average_call_count_of_successful_processes: call_count_of_successful_processes / (successful_processes.max(1) as f64) }
+        for priority_class in priority_classes.values_mut() {
+            priority_class.average_waiting_time /= priority_class.processes_count as f64;
+        }
+        for group in groups.values_mut() {
+            group.average_waiting_time /= group.processes_count as f64;
+            group.processor_share = group.processor_time as f64 / working_time.max(1) as f64;
+        }
+        Ok(SimulationStatistics { average_waiting_time: average_waiting_time / avg_div, processes_count, longest_waiting_time, average_call_count: average_call_count / avg_div, average_partial_waiting_time: average_partial_waiting_time / avg_div, processes_with_lifetime, finished_processes, successful_processes, successful_lifetime_processes, average_call_count_of_successful_processes: call_count_of_successful_processes / (successful_processes.max(1) as f64), priority_classes, groups, context_switches_count, context_switches_time, working_time, trace, cores_utilization, migrations_count, io_utilization, prediction_error, fairness_deviation, processes_shares, abandoned_count, waiting_time: TimeStatistics::new(waiting_times), turnaround_time: TimeStatistics::new(turnaround_times), response_time: TimeStatistics::new(response_times), idle_time, makespan })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SimulationSettings {
+    pub quant: Time,
+    pub mlfq_quantum_times: Vec<Time>,
+    pub mlfq_boost_period: Option<Time>,
+    pub aging_period: Option<Time>,
+    pub context_switch_cost: Time,
+    pub trace_directory: Option<String>,
+    pub workload_output: Option<String>,
+    pub cores_count: usize,
+    pub load_balance_period: Time,
+    pub prediction_alpha: f64,
+    pub initial_burst_estimate: f64,
+    pub cfs_target_latency: Time,
+    pub cfs_minimum_granularity: Time,
+    pub lottery_seed: Option<u64>,
+    pub abandon_hopeless_processes: bool,
+    pub adaptive_minimum_quant: Time,
+    pub adaptive_maximum_quant: Time,
+    pub adaptive_target_latency: Time,
+    pub group_shares: Vec<u32>, // Share of every group by its number, groups without share get 1
+    pub group_policy: fair_share::GroupPolicy,
+    pub algorithms: Option<Vec<String>>, // None - every algorithm, names are compared case insensitively
+}
+
+impl Default for SimulationSettings {
+    fn default() -> Self {
+        Self { quant: 5, mlfq_quantum_times: vec![5, 10, 20], mlfq_boost_period: Some(200), aging_period: Some(50), context_switch_cost: 0, trace_directory: None, workload_output: None, cores_count: 2, load_balance_period: 50, prediction_alpha: 0.5, initial_burst_estimate: 50f64, cfs_target_latency: 20, cfs_minimum_granularity: 4, lottery_seed: None, abandon_hopeless_processes: false, adaptive_minimum_quant: 2, adaptive_maximum_quant: 100, adaptive_target_latency: 50, group_shares: Vec::new(), group_policy: fair_share::GroupPolicy::RoundRobin, algorithms: None }
+    }
+}
+
+impl SimulationSettings {
+    #[inline]
+    pub fn is_selected(&self, name: &str) -> bool {
+        self.algorithms.as_ref().is_none_or(|algorithms| algorithms.iter().any(|algorithm| algorithm.eq_ignore_ascii_case(name)))
+    }
+}
+
+// Registered schedulers of given names which are selected in settings, scheduler is created only if it is selected, so parameters of the other ones aren't validated
+fn simulate_named(registry: &Registry, names: Vec<&str>, process_list: &Processes, settings: &SimulationSettings) -> Result<Vec<(String, SimulationStatistics)>, SimulationError> {
+    names.into_iter().filter(|name| settings.is_selected(name)).map(|name| {
+        let cpu = registry.create(name, settings)?.ok_or(SimulationError::InvalidParameter("Unknown scheduler"))?;
+        Ok((name.to_owned(), Simulation::new(cpu, process_list.clone(), settings.trace_directory.is_some()).simulate()?))
+    }).collect()
+}
+
+// Only schedulers which use settings.quant, so the same workload can be compared across quantum values
+pub fn simulate_quantum_based(process_list: Processes, settings: &SimulationSettings) -> Result<Vec<(String, SimulationStatistics)>, SimulationError> {
+    if settings.quant == 0 {
+        return Err(SimulationError::InvalidParameter("Quant cannot be 0"));
     }
+    let registry = Registry::new();
+    simulate_named(&registry, registry.get_quantum_based_names(), &process_list, settings)
 }
 
-pub fn simulate_every(process_list: Processes, quant: u32) -> Vec<(String, SimulationStatistics)> {
-    if quant == 0 {
-        panic!("Quant cannot be 0");
+// Results of quantum based schedulers for every quantum value, traces are not recorded
+#[allow(clippy::type_complexity)]
+pub fn sweep_quantum<I: IntoIterator<Item = Time>>(process_list: Processes, settings: &SimulationSettings, quantum_values: I) -> Result<Vec<(Time, Vec<(String, SimulationStatistics)>)>, SimulationError> {
+    quantum_values.into_iter().map(|quant| Ok((quant, simulate_quantum_based(process_list.clone(), &SimulationSettings { quant, trace_directory: None, ..settings.clone() })?))).collect()
+}
+
+pub fn simulate_every(process_list: Processes, settings: &SimulationSettings) -> Result<Vec<(String, SimulationStatistics)>, SimulationError> {
+    if settings.quant == 0 {
+        return Err(SimulationError::InvalidParameter("Quant cannot be 0"));
+    }
+    simulate_registered(&Registry::new(), process_list, settings)
+}
+
+// Every scheduler of the registry selected in settings, in order of registration, e.g. the built-in ones together with in-house ones
+pub fn simulate_registered(registry: &Registry, process_list: Processes, settings: &SimulationSettings) -> Result<Vec<(String, SimulationStatistics)>, SimulationError> {
+    simulate_named(registry, registry.get_names(), &process_list, settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::invariant_tests::random_workload;
+
+    use super::*;
+
+    // Names accepted by command line have to be the ones simulate_every reports, unselected schedulers don't even validate their parameters
+    #[test]
+    fn only_selected_algorithms_are_simulated() {
+        let workload = random_workload(7);
+        let names: Vec<String> = simulate_every(workload.clone(), &SimulationSettings::default()).unwrap().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, Registry::new().get_names());
+        let settings = SimulationSettings { algorithms: Some(vec!["fcfs".to_owned(), "Rate Monotonic".to_owned()]), mlfq_quantum_times: Vec::new(), ..SimulationSettings::default() };
+        let names: Vec<String> = simulate_every(workload.clone(), &settings).unwrap().into_iter().map(|(name, _)| name).collect();
+        assert_eq!(names, ["FCFS", "Rate monotonic"]);
+        assert!(simulate_quantum_based(workload, &settings).unwrap().is_empty());
+    }
+
+    // Ratio is defined only when some process has lifetime
+    #[test]
+    fn deadline_hit_ratio_needs_lifetime_processes() {
+        let statistics = |workload: Processes| simulate_every(workload, &SimulationSettings { algorithms: Some(vec!["FCFS".to_owned()]), ..SimulationSettings::default() }).unwrap().remove(0).1;
+        let without_lifetime = statistics(Processes::from(vec![(0, 5, None), (0, 5, None)]));
+        assert_eq!((without_lifetime.get_deadline_hit_ratio(), without_lifetime.get_successful_lifetime_processes_count()), (None, 0));
+        let with_lifetime = statistics(Processes::from(vec![(0, 5, None), (0, 5, Some(20)), (0, 5, Some(7))]));
+        assert_eq!((with_lifetime.get_deadline_hit_ratio(), with_lifetime.get_successful_lifetime_processes_count()), (Some(0.5), 1));
+        let abandoning = simulate_every(Processes::from(vec![(0, 5, None), (0, 10, Some(8)), (0, 3, Some(20))]), &SimulationSettings { algorithms: Some(vec!["EDF".to_owned()]), abandon_hopeless_processes: true, ..SimulationSettings::default() }).unwrap().remove(0).1;
+        assert_eq!((abandoning.get_abandoned_count(), abandoning.get_successful_lifetime_processes_count(), abandoning.get_deadline_hit_ratio()), (Some(1), 1, Some(0.5)));
     }
-    let mut ans = Vec::with_capacity(4);
-    ans.push(("FCFS".to_owned(), Simulation::new(fcfs::FCFS::new(), process_list.clone()).simulate()));
-    ans.push(("SJF".to_owned(), Simulation::new(sjf::SJF::new(), process_list.clone()).simulate()));
-    ans.push(("SJF with preemption".to_owned(), Simulation::new(sjf::SJFWithPreemption::new(), process_list.clone()).simulate()));
-    ans.push(("Rotating".to_owned(), Simulation::new(rotating::Rotating::new(quant), process_list).simulate()));
-    ans
 }
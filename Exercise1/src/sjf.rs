@@ -1,46 +1,61 @@
 use std::cmp::Reverse;
 
-use crate::cpu_access_manager::{self, CpuAccessManager, Process};
+use crate::cpu_access_manager::{self, CpuAccessManager, Process, ProcessDescription, SimulationError, Time};
+use crate::io_device::IoDevice;
+use crate::trace::Trace;
 
 #[derive(Debug, Clone)]
+#[allow(clippy::upper_case_acronyms)]
 pub struct SJF {
     queue: std::collections::BinaryHeap<Reverse<Process>>,
     current_process: Option<Process>,
-    working_time: u32,
-    waiting_time: u32,
-    total_working_time: u32,
-    total_waiting_time: u32,
+    is_ending: bool,
+    working_time: Time,
+    waiting_time: Time,
+    total_working_time: Time,
+    total_waiting_time: Time,
     next_id: u32,
-    current_time: u32,
+    current_time: Time,
     statistics: Vec<cpu_access_manager::ProcessStatistics>,
-    total_required_time: u32,
+    total_required_time: Time,
+    context_switch: cpu_access_manager::ContextSwitch,
+    trace: Trace,
+    io_device: IoDevice<Process>,
 }
 
 impl SJF {
     #[inline]
-    pub fn new() -> Self {
-        Self { queue: std::collections::BinaryHeap::new(), current_process: None, working_time: 0, waiting_time: 0, total_working_time: 0, total_waiting_time: 0, next_id: 0, current_time: 0, statistics: Vec::new(), total_required_time: 0 }
+    pub fn new(context_switch_cost: Time) -> Self {
+        Self { queue: std::collections::BinaryHeap::new(), current_process: None, is_ending: false, working_time: 0, waiting_time: 0, total_working_time: 0, total_waiting_time: 0, next_id: 0, current_time: 0, statistics: Vec::new(), total_required_time: 0, context_switch: cpu_access_manager::ContextSwitch::new(context_switch_cost), trace: Trace::new(), io_device: IoDevice::new() }
+    }
+
+    fn return_from_io(&mut self) {
+        for (mut process, time) in self.io_device.advance_to(self.current_time) {
+            process.end_io(time);
+            self.trace.io_completion(time, process.get_id());
+            self.queue.push(Reverse(process));
+        }
     }
 }
 
 impl CpuAccessManager for SJF {
-    fn add_process(&mut self, time: u32, lifetime: Option<u32>) -> u32 {
-        if time == 0 {
-            panic!("Process can't have duration time of 0");
-        }
-        self.queue.push(Reverse(Process::new(self.next_id, self.current_time, time, lifetime)));
+    fn add_process(&mut self, description: &ProcessDescription) -> Result<u32, SimulationError> {
+        description.validate()?;
+        let total_required_time = self.total_required_time.checked_add(description.get_total_duration()).ok_or(SimulationError::TimeOverflow)?;
+        self.queue.push(Reverse(Process::new(self.next_id, self.current_time, description)));
         let ans = self.next_id;
+        self.trace.arrival(self.current_time, ans);
         self.next_id += 1;
         if self.current_process.is_none() {
             self.current_process = self.queue.pop().map(|v| v.0);
         }
-        self.total_required_time += time;
-        ans
+        self.total_required_time = total_required_time;
+        Ok(ans)
     }
 
     #[inline]
     fn is_working(&self) -> bool {
-        self.current_process.is_some()
+        self.current_process.is_some() || !self.queue.is_empty() || self.io_device.is_working()
     }
 
     #[inline]
@@ -48,57 +63,112 @@ impl CpuAccessManager for SJF {
         self.simulate_n_ticks(1)
     }
 
-    fn simulate_n_ticks(&mut self, mut n: u32) {
+    fn simulate_n_ticks(&mut self, mut n: Time) {
         while n != 0 {
+            self.return_from_io();
+            if self.is_ending {
+                n = n.min(self.total_required_time);
+            }
+            if self.current_process.is_none() {
+                self.current_process = self.queue.pop().map(|v| v.0);
+            }
             if let Some(mut current) = self.current_process.take() {
-                let worked_time = current.work_for(self.current_time, n, current.get_initial_time() == current.get_time_left());
+                let switch_time = self.context_switch.switch_to(current.get_id(), self.io_device.limit(self.current_time, n));
+                n -= switch_time;
+                self.current_time += switch_time;
+                if self.context_switch.is_switching() {
+                    self.current_process = Some(current);
+                    continue;
+                }
+                let worked_time = current.work_for(self.current_time, self.io_device.limit(self.current_time, n), current.is_at_burst_start());
                 n -= worked_time;
+                self.trace.run(current.get_id(), self.current_time, worked_time);
                 self.current_time += worked_time;
                 self.total_required_time -= worked_time;
+                self.total_working_time += worked_time;
                 if current.is_finished() {
+                    self.trace.finish(self.current_time, &current);
+                    self.total_required_time -= current.get_total_time_left();
                     self.statistics.push(current.finalize());
                     self.current_process = self.queue.pop().map(|v| v.0);
                 }
+                else if current.is_blocked() {
+                    let io_time = current.start_io();
+                    self.trace.block(self.current_time, &current);
+                    self.io_device.block(self.current_time, current, io_time);
+                    self.current_process = self.queue.pop().map(|v| v.0);
+                }
                 else {
                     self.current_process = Some(current);
                 }
             }
             else {
-                self.waiting_time = n;
+                let idle_time = self.io_device.limit(self.current_time, n);
+                self.waiting_time = idle_time;
                 self.working_time = 0;
                 self.total_waiting_time += self.waiting_time;
-                return;
+                self.current_time += idle_time;
+                n -= idle_time;
             }
         }
     }
 
     #[inline]
-    fn get_total_working_time(&self) -> u32 {
+    fn get_total_working_time(&self) -> Time {
         self.total_working_time
     }
 
     #[inline]
-    fn get_total_waiting_time(&self) -> u32 {
+    fn get_total_waiting_time(&self) -> Time {
         self.total_waiting_time
     }
 
     #[inline]
-    fn get_working_time(&self) -> u32 {
+    fn get_working_time(&self) -> Time {
         self.working_time
     }
 
     #[inline]
-    fn get_waiting_time(&self) -> u32 {
+    fn get_waiting_time(&self) -> Time {
         self.waiting_time
     }
 
+    #[inline]
+    fn get_context_switches_count(&self) -> usize {
+        self.context_switch.get_count()
+    }
+
+    #[inline]
+    fn get_context_switches_time(&self) -> Time {
+        self.context_switch.get_total_time()
+    }
+
+    #[inline]
+    fn get_io_utilization(&self) -> f64 {
+        self.io_device.get_working_time() as f64 / self.current_time.max(1) as f64
+    }
+
+    #[inline]
+    fn enable_trace(&mut self) {
+        self.trace.enable()
+    }
+
+    #[inline]
+    fn get_trace(&self) -> &Trace {
+        &self.trace
+    }
+
     #[inline]
     fn simulate_till_end_of_every_process(&mut self) {
-        self.simulate_n_ticks(self.total_required_time)
+        self.is_ending = true;
+        while self.is_working() {
+            self.simulate_n_ticks(self.total_required_time)
+        }
+        self.is_ending = false;
     }
 
     #[inline]
-    fn finalize(mut self) -> Vec<cpu_access_manager::ProcessStatistics> {
+    fn finalize(mut self: Box<Self>) -> Vec<cpu_access_manager::ProcessStatistics> {
         self.simulate_till_end_of_every_process();
         self.statistics
     }
@@ -107,39 +177,51 @@ impl CpuAccessManager for SJF {
 #[derive(Debug, Clone)]
 pub struct SJFWithPreemption {
     queue: std::collections::BinaryHeap<Reverse<Process>>,
-    working_time: u32,
-    waiting_time: u32,
-    total_working_time: u32,
-    total_waiting_time: u32,
+    is_ending: bool,
+    working_time: Time,
+    waiting_time: Time,
+    total_working_time: Time,
+    total_waiting_time: Time,
     next_id: u32,
-    current_time: u32,
+    current_time: Time,
     statistics: Vec<cpu_access_manager::ProcessStatistics>,
-    total_required_time: u32,
+    total_required_time: Time,
     current_id: Option<u32>,
+    context_switch: cpu_access_manager::ContextSwitch,
+    trace: Trace,
+    io_device: IoDevice<Process>,
 }
 
 impl SJFWithPreemption {
     #[inline]
-    pub fn new() -> Self {
-        Self { queue: std::collections::BinaryHeap::new(), working_time: 0, waiting_time: 0, total_working_time: 0, total_waiting_time: 0, next_id: 0, current_time: 0, statistics: Vec::new(), total_required_time: 0, current_id: None }
+    pub fn new(context_switch_cost: Time) -> Self {
+        Self { queue: std::collections::BinaryHeap::new(), is_ending: false, working_time: 0, waiting_time: 0, total_working_time: 0, total_waiting_time: 0, next_id: 0, current_time: 0, statistics: Vec::new(), total_required_time: 0, current_id: None, context_switch: cpu_access_manager::ContextSwitch::new(context_switch_cost), trace: Trace::new(), io_device: IoDevice::new() }
+    }
+
+    fn return_from_io(&mut self) {
+        for (mut process, time) in self.io_device.advance_to(self.current_time) {
+            process.end_io(time);
+            self.trace.io_completion(time, process.get_id());
+            self.queue.push(Reverse(process));
+        }
     }
 }
 
 impl CpuAccessManager for SJFWithPreemption {
-    fn add_process(&mut self, time: u32, lifetime: Option<u32>) -> u32 {
-        if time == 0 {
-            panic!("Process can't have duration time of 0");
-        }
-        self.queue.push(Reverse(Process::new(self.next_id, self.current_time, time, lifetime)));
+    fn add_process(&mut self, description: &ProcessDescription) -> Result<u32, SimulationError> {
+        description.validate()?;
+        let total_required_time = self.total_required_time.checked_add(description.get_total_duration()).ok_or(SimulationError::TimeOverflow)?;
+        self.queue.push(Reverse(Process::new(self.next_id, self.current_time, description)));
         let ans = self.next_id;
+        self.trace.arrival(self.current_time, ans);
         self.next_id += 1;
-        self.total_required_time += time;
-        ans
+        self.total_required_time = total_required_time;
+        Ok(ans)
     }
 
     #[inline]
     fn is_working(&self) -> bool {
-        !self.queue.is_empty()
+        !self.queue.is_empty() || self.io_device.is_working()
     }
 
     #[inline]
@@ -147,62 +229,112 @@ impl CpuAccessManager for SJFWithPreemption {
         self.simulate_n_ticks(1)
     }
 
-    fn simulate_n_ticks(&mut self, mut n: u32) {
+    fn simulate_n_ticks(&mut self, mut n: Time) {
         while n != 0 {
+            self.return_from_io();
+            if self.is_ending {
+                n = n.min(self.total_required_time);
+            }
             if let Some(Reverse(mut current)) = self.queue.pop() {
-                let worked_time = current.work_for(self.current_time, n, match self.current_id.is_none() || self.current_id.unwrap() != current.get_id() {
-                    true => {
-                        self.current_id = Some(current.get_id());
-                        true
-                    },
-                    false => false,
-                });
+                let switch_time = self.context_switch.switch_to(current.get_id(), self.io_device.limit(self.current_time, n));
+                n -= switch_time;
+                self.current_time += switch_time;
+                if self.context_switch.is_switching() {
+                    self.queue.push(Reverse(current));
+                    continue;
+                }
+                let is_new_call = self.current_id != Some(current.get_id());
+                let worked_time = current.work_for(self.current_time, self.io_device.limit(self.current_time, n), is_new_call);
+                if worked_time != 0 {
+                    self.current_id = Some(current.get_id());
+                }
                 n -= worked_time;
+                self.trace.run(current.get_id(), self.current_time, worked_time);
                 self.current_time += worked_time;
                 self.total_required_time -= worked_time;
+                self.total_working_time += worked_time;
                 if current.is_finished() {
+                    self.trace.finish(self.current_time, &current);
+                    self.total_required_time -= current.get_total_time_left();
                     self.statistics.push(current.finalize());
                 }
+                else if current.is_blocked() {
+                    let io_time = current.start_io();
+                    self.trace.block(self.current_time, &current);
+                    self.io_device.block(self.current_time, current, io_time);
+                    self.current_id = None;
+                }
                 else {
                     self.queue.push(Reverse(current));
                 }
             }
             else {
-                self.waiting_time = n;
+                let idle_time = self.io_device.limit(self.current_time, n);
+                self.waiting_time = idle_time;
                 self.working_time = 0;
                 self.total_waiting_time += self.waiting_time;
-                return;
+                self.current_time += idle_time;
+                n -= idle_time;
             }
         }
     }
 
     #[inline]
-    fn get_total_working_time(&self) -> u32 {
+    fn get_total_working_time(&self) -> Time {
         self.total_working_time
     }
 
     #[inline]
-    fn get_total_waiting_time(&self) -> u32 {
+    fn get_total_waiting_time(&self) -> Time {
         self.total_waiting_time
     }
 
     #[inline]
-    fn get_working_time(&self) -> u32 {
+    fn get_working_time(&self) -> Time {
         self.working_time
     }
 
     #[inline]
-    fn get_waiting_time(&self) -> u32 {
+    fn get_waiting_time(&self) -> Time {
         self.waiting_time
     }
 
+    #[inline]
+    fn get_context_switches_count(&self) -> usize {
+        self.context_switch.get_count()
+    }
+
+    #[inline]
+    fn get_context_switches_time(&self) -> Time {
+        self.context_switch.get_total_time()
+    }
+
+    #[inline]
+    fn get_io_utilization(&self) -> f64 {
+        self.io_device.get_working_time() as f64 / self.current_time.max(1) as f64
+    }
+
+    #[inline]
+    fn enable_trace(&mut self) {
+        self.trace.enable()
+    }
+
+    #[inline]
+    fn get_trace(&self) -> &Trace {
+        &self.trace
+    }
+
     #[inline]
     fn simulate_till_end_of_every_process(&mut self) {
-        self.simulate_n_ticks(self.total_required_time)
+        self.is_ending = true;
+        while self.is_working() {
+            self.simulate_n_ticks(self.total_required_time)
+        }
+        self.is_ending = false;
     }
 
     #[inline]
-    fn finalize(mut self) -> Vec<cpu_access_manager::ProcessStatistics> {
+    fn finalize(mut self: Box<Self>) -> Vec<cpu_access_manager::ProcessStatistics> {
         self.simulate_till_end_of_every_process();
         self.statistics
     }
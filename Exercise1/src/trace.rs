@@ -0,0 +1,318 @@
+use std::fmt::Write;
+
+use crate::cpu_access_manager::{Process, Time};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Arrival,
+    Dispatch,
+    Preemption,
+    Completion,
+    LifetimeExpiry,
+    Abandonment,
+    IoBlock,
+    IoCompletion,
+}
+
+impl EventKind {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            EventKind::Arrival => "arrival",
+            EventKind::Dispatch => "dispatch",
+            EventKind::Preemption => "preemption",
+            EventKind::Completion => "completion",
+            EventKind::LifetimeExpiry => "lifetime_expiry",
+            EventKind::Abandonment => "abandonment",
+            EventKind::IoBlock => "io_block",
+            EventKind::IoCompletion => "io_completion",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Event {
+    time: Time,
+    process_id: u32,
+    kind: EventKind,
+    core: Option<usize>,
+}
+
+impl Event {
+    #[inline]
+    pub fn get_time(&self) -> Time {
+        self.time
+    }
+
+    #[inline]
+    pub fn get_process_id(&self) -> u32 {
+        self.process_id
+    }
+
+    #[inline]
+    pub fn get_kind(&self) -> EventKind {
+        self.kind
+    }
+
+    #[inline]
+    pub fn get_core(&self) -> Option<usize> {
+        self.core
+    }
+}
+
+// Single period of time during which process was using processor
+#[derive(Debug, Clone, Copy)]
+pub struct Slice {
+    process_id: u32,
+    core: usize,
+    start: Time,
+    end: Time,
+}
+
+impl Slice {
+    #[inline]
+    pub fn get_process_id(&self) -> u32 {
+        self.process_id
+    }
+
+    #[inline]
+    pub fn get_core(&self) -> usize {
+        self.core
+    }
+
+    #[inline]
+    pub fn get_start(&self) -> Time {
+        self.start
+    }
+
+    #[inline]
+    pub fn get_end(&self) -> Time {
+        self.end
+    }
+}
+
+// Trace is disabled by default, so long simulations don't have to keep every event in memory
+#[derive(Debug, Clone, Default)]
+pub struct Trace {
+    enabled: bool,
+    events: Vec<Event>,
+    slices: Vec<Slice>,
+    running: Vec<Option<(u32, usize)>>, // Process id and index of its last slice, for every core
+}
+
+impl Trace {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn enable(&mut self) {
+        self.enabled = true;
+    }
+
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    #[inline]
+    pub fn get_events(&self) -> &[Event] {
+        &self.events
+    }
+
+    #[inline]
+    pub fn get_slices(&self) -> &[Slice] {
+        &self.slices
+    }
+
+    #[inline]
+    fn push(&mut self, time: Time, process_id: u32, kind: EventKind, core: Option<usize>) {
+        self.events.push(Event { time, process_id, kind, core });
+    }
+
+    #[inline]
+    pub fn arrival(&mut self, time: Time, process_id: u32) {
+        if self.enabled {
+            self.push(time, process_id, EventKind::Arrival, None);
+        }
+    }
+
+    // Has to be called every time process works, dispatch and preemption are detected by change of the running process
+    #[inline]
+    pub fn run(&mut self, process_id: u32, start_time: Time, worked_time: Time) {
+        self.run_on_core(0, process_id, start_time, worked_time)
+    }
+
+    pub fn run_on_core(&mut self, core: usize, process_id: u32, start_time: Time, worked_time: Time) {
+        if !self.enabled {
+            return;
+        }
+        if self.running.len() <= core {
+            self.running.resize(core + 1, None);
+        }
+        match self.running[core] {
+            Some((running_id, slice_index)) if running_id == process_id => {
+                let slice = &mut self.slices[slice_index];
+                if slice.end == start_time {
+                    slice.end += worked_time;
+                    return;
+                }
+            },
+            Some((previous, slice_index)) => {
+                let end = self.slices[slice_index].end;
+                self.push(end, previous, EventKind::Preemption, Some(core));
+                self.push(start_time, process_id, EventKind::Dispatch, Some(core));
+            },
+            None => self.push(start_time, process_id, EventKind::Dispatch, Some(core)),
+        }
+        self.running[core] = Some((process_id, self.slices.len()));
+        self.slices.push(Slice { process_id, core, start: start_time, end: start_time + worked_time });
+    }
+
+    // Process stops running, core on which it was running last time becomes free
+    fn leave(&mut self, time: Time, process_id: u32, kind: EventKind) {
+        if !self.enabled {
+            return;
+        }
+        let core = self.running.iter().position(|running| running.is_some_and(|(id, _)| id == process_id));
+        self.push(time, process_id, kind, core);
+        if let Some(core) = core {
+            self.running[core] = None;
+        }
+    }
+
+    #[inline]
+    pub fn finish(&mut self, time: Time, process: &Process) {
+        self.leave(time, process.get_id(), match process.is_successful() {
+            Some(true) => EventKind::Completion,
+            _ => EventKind::LifetimeExpiry,
+        })
+    }
+
+    #[inline]
+    pub fn abandon(&mut self, time: Time, process: &Process) {
+        self.leave(time, process.get_id(), EventKind::Abandonment)
+    }
+
+    #[inline]
+    pub fn block(&mut self, time: Time, process: &Process) {
+        self.leave(time, process.get_id(), EventKind::IoBlock)
+    }
+
+    #[inline]
+    pub fn io_completion(&mut self, time: Time, process_id: u32) {
+        if self.enabled {
+            self.push(time, process_id, EventKind::IoCompletion, None);
+        }
+    }
+
+    #[inline]
+    fn get_end_time(&self) -> Time {
+        self.events.iter().map(|event| event.time).max().unwrap_or(0)
+    }
+
+    // Arrival and finish time of every process, indexed by process id
+    fn get_lifespans(&self) -> Vec<(Time, Time)> {
+        let mut lifespans = Vec::new();
+        for event in self.events.iter() {
+            let id = event.process_id as usize;
+            if lifespans.len() <= id {
+                lifespans.resize(id + 1, (0, 0));
+            }
+            match event.kind {
+                EventKind::Arrival => lifespans[id] = (event.time, event.time),
+                EventKind::Completion | EventKind::LifetimeExpiry | EventKind::Abandonment => lifespans[id].1 = event.time,
+                EventKind::Dispatch | EventKind::Preemption | EventKind::IoBlock | EventKind::IoCompletion => (),
+            }
+        }
+        lifespans
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut ans = String::from("time,process_id,event,core\n");
+        // Cores are simulated one after another, so events of a single step may be out of order
+        let mut events: Vec<&Event> = self.events.iter().collect();
+        events.sort_by_key(|event| event.time);
+        for event in events {
+            let _ = writeln!(ans, "{},{},{},{}", event.time, event.process_id, event.kind.get_name(), event.core.map(|core| core.to_string()).unwrap_or_default());
+        }
+        ans
+    }
+
+    // One row per process, '#' - running, '.' - waiting, every column covers the same amount of time
+    pub fn to_ascii_gantt(&self, width: usize) -> String {
+        let end_time = self.get_end_time() as usize;
+        let width = width.max(1);
+        let column_time = end_time.div_ceil(width).max(1);
+        let columns = end_time.div_ceil(column_time);
+        let lifespans = self.get_lifespans();
+        let mut rows: Vec<Vec<u8>> = lifespans.iter().map(|(arrival, finish)| (0..columns).map(|column| match column * column_time < *finish as usize && (column + 1) * column_time > *arrival as usize {
+            true => b'.',
+            false => b' ',
+        }).collect()).collect();
+        for slice in self.slices.iter().filter(|slice| slice.start != slice.end) {
+            rows[slice.process_id as usize][(slice.start as usize / column_time)..(slice.end as usize).div_ceil(column_time)].fill(b'#');
+        }
+        let label_width = lifespans.len().saturating_sub(1).to_string().len() + 1;
+        let mut ans = format!("{:label_width$}0{:>width$}\n", "", end_time, width = columns + 1);
+        for (id, row) in rows.into_iter().enumerate() {
+            let _ = writeln!(ans, "{:<label_width$}|{}|", format!("P{}", id), String::from_utf8(row).unwrap(), label_width = label_width);
+        }
+        ans
+    }
+
+    pub fn to_svg(&self) -> String {
+        const ROW_HEIGHT: u32 = 20;
+        const LABEL_WIDTH: u32 = 60;
+        const CHART_WIDTH: u32 = 1000;
+        let end_time = self.get_end_time().max(1);
+        let lifespans = self.get_lifespans();
+        let scale = CHART_WIDTH as f64 / end_time as f64;
+        let height = (lifespans.len() as u32 + 1) * ROW_HEIGHT;
+        let mut ans = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" font-family=\"monospace\" font-size=\"12\">\n", LABEL_WIDTH + CHART_WIDTH + 10, height);
+        for (id, (arrival, finish)) in lifespans.iter().enumerate() {
+            let y = id as u32 * ROW_HEIGHT;
+            let _ = writeln!(ans, "<text x=\"0\" y=\"{}\">P{}</text>", y + ROW_HEIGHT - 6, id);
+            let _ = writeln!(ans, "<rect x=\"{:.2}\" y=\"{}\" width=\"{:.2}\" height=\"{}\" fill=\"#dddddd\"/>", LABEL_WIDTH as f64 + *arrival as f64 * scale, y + 4, (finish - arrival) as f64 * scale, ROW_HEIGHT - 8);
+        }
+        for slice in self.slices.iter().filter(|slice| slice.start != slice.end) {
+            let _ = writeln!(ans, "<rect x=\"{:.2}\" y=\"{}\" width=\"{:.2}\" height=\"{}\" fill=\"#3366cc\"><title>P{}: {}-{} (core {})</title></rect>", LABEL_WIDTH as f64 + slice.start as f64 * scale, slice.process_id * ROW_HEIGHT + 2, (slice.end - slice.start) as f64 * scale, ROW_HEIGHT - 4, slice.process_id, slice.start, slice.end, slice.core);
+        }
+        let _ = writeln!(ans, "<text x=\"{}\" y=\"{}\">0</text>", LABEL_WIDTH, height - 4);
+        let _ = writeln!(ans, "<text x=\"{}\" y=\"{}\" text-anchor=\"end\">{}</text>", LABEL_WIDTH + CHART_WIDTH, height - 4, end_time);
+        ans.push_str("</svg>\n");
+        ans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cpu_access_manager::{CpuAccessManager, ProcessDescription};
+    use crate::rotating::Rotating;
+
+    use super::*;
+
+    // Process 0 is preempted by process 1 at the end of its quantum and finishes after it
+    fn get_trace() -> Trace {
+        let mut cpu = Rotating::new(4, 0);
+        cpu.enable_trace();
+        cpu.add_process(&ProcessDescription::new(6, None, 0)).unwrap();
+        cpu.simulate_n_ticks(2);
+        cpu.add_process(&ProcessDescription::new(3, None, 0)).unwrap();
+        cpu.simulate_till_end_of_every_process();
+        cpu.get_trace().clone()
+    }
+
+    #[test]
+    fn exports_of_preemption() {
+        let trace = get_trace();
+        assert_eq!(trace.to_csv(), "time,process_id,event,core\n0,0,arrival,\n0,0,dispatch,0\n2,1,arrival,\n4,0,preemption,0\n4,1,dispatch,0\n7,1,completion,0\n7,0,dispatch,0\n9,0,completion,0\n");
+        assert_eq!(trace.to_ascii_gantt(9), "  0         9\nP0|####...##|\nP1|  ..###  |\n");
+        // Every column covers 2 units of time, column is running if process runs in any part of it
+        assert_eq!(trace.to_ascii_gantt(5), "  0     9\nP0|##.##|\nP1| .## |\n");
+        let svg = trace.to_svg();
+        assert!(svg.starts_with("<svg ") && svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("fill=\"#3366cc\"").count(), 3);
+        assert!(svg.contains("<title>P1: 4-7 (core 0)</title>"));
+    }
+}
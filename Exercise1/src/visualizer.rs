@@ -0,0 +1,377 @@
+// Step by step simulation in terminal, tick by tick or event by event, with navigation back to any visited time.
+// Scheduler is driven with simulate_one_tick, what is shown at any time is rebuilt from its trace,
+// so only cumulative statistics of every simulated tick have to be kept.
+
+use std::collections::VecDeque;
+
+use ratatui::crossterm::event::{self, Event as TerminalEvent, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, List, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+
+use zad1::cpu_access_manager::{CpuAccessManager, ProcessDescription, SimulationError, Time};
+use zad1::simulator::Processes;
+use zad1::trace::{Event, EventKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessState {
+    Ready,
+    Running(usize), // Core on which process runs
+    Blocked,
+    Completed,
+    Expired,
+    Abandoned,
+}
+
+// Process which has already arrived at the time of snapshot
+#[derive(Debug, Clone)]
+pub struct ProcessView {
+    id: u32,
+    state: ProcessState,
+    arrival_time: Time,
+    remaining_time: Time, // Processor time of every burst which is still left
+    priority: u32,
+    group: u32,
+    deadline: Option<Time>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Counters {
+    working_time: Time,
+    idle_time: Time,
+    context_switches_count: usize,
+    context_switches_time: Time,
+}
+
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    time: Time,
+    processes: Vec<ProcessView>,
+    counters: Counters,
+    recent_events: Vec<Event>,
+}
+
+impl Snapshot {
+    #[inline]
+    fn get_count(&self, state: ProcessState) -> usize {
+        self.processes.iter().filter(|process| process.state == state).count()
+    }
+}
+
+pub struct Stepper {
+    cpu: Box<dyn CpuAccessManager>,
+    arrivals: VecDeque<(Time, ProcessDescription)>, // Absolute arrival times
+    processes: Vec<(Time, ProcessDescription)>, // Arrival time and description of every added process, by its id
+    counters: Vec<Counters>, // After every simulated tick, the first one is the start
+    position: Time,
+}
+
+impl Stepper {
+    pub fn new(mut cpu: Box<dyn CpuAccessManager>, process_list: Processes) -> Result<Self, SimulationError> {
+        cpu.enable_trace();
+        let mut arrival_time: Time = 0;
+        let mut arrivals = VecDeque::new();
+        for (wait, description) in process_list.iter() {
+            arrival_time = arrival_time.checked_add(*wait).ok_or(SimulationError::TimeOverflow)?;
+            arrivals.push_back((arrival_time, description.clone()));
+        }
+        let mut ans = Self { cpu, arrivals, processes: Vec::new(), counters: vec![Counters::default()], position: 0 };
+        ans.add_arrivals()?;
+        Ok(ans)
+    }
+
+    #[inline]
+    pub fn get_position(&self) -> Time {
+        self.position
+    }
+
+    #[inline]
+    pub fn get_simulated_time(&self) -> Time {
+        (self.counters.len() - 1) as Time
+    }
+
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.arrivals.is_empty() && !self.cpu.is_working()
+    }
+
+    // Processes arriving at the current time are added before the next tick, like in the whole simulation
+    fn add_arrivals(&mut self) -> Result<(), SimulationError> {
+        while let Some((arrival_time, description)) = self.arrivals.pop_front() {
+            if arrival_time != self.get_simulated_time() {
+                self.arrivals.push_front((arrival_time, description));
+                break;
+            }
+            let id = self.cpu.add_process(&description)? as usize;
+            if self.processes.len() <= id {
+                self.processes.resize(id + 1, (0, description.clone()));
+            }
+            self.processes[id] = (arrival_time, description);
+        }
+        Ok(())
+    }
+
+    fn simulate_tick(&mut self) -> Result<(), SimulationError> {
+        self.cpu.simulate_one_tick();
+        self.counters.push(Counters { working_time: self.cpu.get_total_working_time(), idle_time: self.cpu.get_total_waiting_time(), context_switches_count: self.cpu.get_context_switches_count(), context_switches_time: self.cpu.get_context_switches_time() });
+        self.add_arrivals()
+    }
+
+    // Simulates as far as needed, position can't go past the end of simulation
+    pub fn go_to(&mut self, time: Time) -> Result<(), SimulationError> {
+        while self.get_simulated_time() < time && !self.is_finished() {
+            self.simulate_tick()?;
+        }
+        self.position = time.min(self.get_simulated_time());
+        Ok(())
+    }
+
+    #[inline]
+    pub fn go_to_end(&mut self) -> Result<(), SimulationError> {
+        self.go_to(Time::MAX)
+    }
+
+    // The nearest later time at which something happens in the trace, e.g. arrival, dispatch or completion
+    pub fn go_to_next_event(&mut self) -> Result<(), SimulationError> {
+        let mut checked_events = 0;
+        loop {
+            let events = self.cpu.get_trace().get_events();
+            if let Some(time) = events[checked_events..].iter().map(Event::get_time).filter(|time| *time > self.position).min() {
+                return self.go_to(time);
+            }
+            checked_events = events.len();
+            if self.is_finished() {
+                self.position = self.get_simulated_time();
+                return Ok(());
+            }
+            self.simulate_tick()?;
+        }
+    }
+
+    pub fn go_to_previous_event(&mut self) {
+        self.position = self.cpu.get_trace().get_events().iter().map(Event::get_time).filter(|time| *time < self.position).max().unwrap_or(0);
+    }
+
+    // State after position ticks, process which worked in the last tick is running, the other ones which were dispatched are ready
+    pub fn get_snapshot(&self) -> Snapshot {
+        let time = self.position;
+        let trace = self.cpu.get_trace();
+        let mut states = vec![None; self.processes.len()];
+        // Preemption is recorded only when the core is taken by another process, possibly after its later events on other cores,
+        // it doesn't change state anyway like dispatch, process which runs is found by slices
+        for event in trace.get_events().iter().filter(|event| event.get_time() <= time && !matches!(event.get_kind(), EventKind::Dispatch | EventKind::Preemption)) {
+            let state = &mut states[event.get_process_id() as usize];
+            *state = Some(match event.get_kind() {
+                EventKind::Arrival | EventKind::Dispatch | EventKind::Preemption | EventKind::IoCompletion => ProcessState::Ready,
+                EventKind::IoBlock => ProcessState::Blocked,
+                EventKind::Completion => ProcessState::Completed,
+                EventKind::LifetimeExpiry => ProcessState::Expired,
+                EventKind::Abandonment => ProcessState::Abandoned,
+            });
+        }
+        let mut executed_times = vec![0; self.processes.len()];
+        for slice in trace.get_slices().iter().filter(|slice| slice.get_start() < time) {
+            executed_times[slice.get_process_id() as usize] += slice.get_end().min(time) - slice.get_start();
+            if slice.get_end() >= time && states[slice.get_process_id() as usize] == Some(ProcessState::Ready) {
+                states[slice.get_process_id() as usize] = Some(ProcessState::Running(slice.get_core()));
+            }
+        }
+        let processes = states.into_iter().zip(executed_times).zip(self.processes.iter()).enumerate().filter_map(|(id, ((state, executed_time), (arrival_time, description)))| Some(ProcessView { id: id as u32, state: state?, arrival_time: *arrival_time, remaining_time: description.get_total_duration().saturating_sub(executed_time), priority: description.get_priority(), group: description.get_group(), deadline: description.get_lifetime().map(|lifetime| arrival_time.saturating_add(lifetime)) })).collect();
+        let events = trace.get_events().iter().filter(|event| event.get_time() <= time).cloned().collect::<Vec<_>>();
+        Snapshot { time, processes, counters: self.counters[time as usize], recent_events: events[events.len().saturating_sub(20)..].to_vec() }
+    }
+}
+
+struct App {
+    name: String,
+    stepper: Stepper,
+    jump_input: Option<String>, // Digits typed after pressing 'g' until Enter
+    message: String,
+}
+
+const HELP: &str = "←/→ tick  p/n event  g jump to time  Home start  End end  q quit";
+
+pub fn run(name: &str, stepper: Stepper) -> std::io::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = run_app(&mut terminal, App { name: name.to_owned(), stepper, jump_input: None, message: HELP.to_owned() });
+    ratatui::restore();
+    result
+}
+
+fn run_app(terminal: &mut DefaultTerminal, mut app: App) -> std::io::Result<()> {
+    loop {
+        let snapshot = app.stepper.get_snapshot();
+        terminal.draw(|frame| draw(frame, &app, &snapshot))?;
+        let TerminalEvent::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let position = app.stepper.get_position();
+        let result = match (&mut app.jump_input, key.code) {
+            (Some(input), KeyCode::Char(digit)) if digit.is_ascii_digit() => {
+                input.push(digit);
+                Ok(())
+            },
+            (Some(input), KeyCode::Backspace) => {
+                input.pop();
+                Ok(())
+            },
+            (Some(input), KeyCode::Enter) => {
+                let time = input.parse().unwrap_or(position);
+                app.jump_input = None;
+                app.stepper.go_to(time)
+            },
+            (Some(_), KeyCode::Esc) => {
+                app.jump_input = None;
+                Ok(())
+            },
+            (Some(_), _) => Ok(()),
+            (None, KeyCode::Char('q') | KeyCode::Esc) => return Ok(()),
+            (None, KeyCode::Right | KeyCode::Char('l')) => app.stepper.go_to(position.saturating_add(1)),
+            (None, KeyCode::Left | KeyCode::Char('h')) => app.stepper.go_to(position.saturating_sub(1)),
+            (None, KeyCode::Char('n')) => app.stepper.go_to_next_event(),
+            (None, KeyCode::Char('p')) => {
+                app.stepper.go_to_previous_event();
+                Ok(())
+            },
+            (None, KeyCode::Char('g')) => {
+                app.jump_input = Some(String::new());
+                Ok(())
+            },
+            (None, KeyCode::Home) => app.stepper.go_to(0),
+            (None, KeyCode::End) => app.stepper.go_to_end(),
+            (None, _) => Ok(()),
+        };
+        app.message = match result {
+            Ok(()) if app.stepper.is_finished() && app.stepper.get_position() == app.stepper.get_simulated_time() => format!("Simulation ended at {}   {}", app.stepper.get_simulated_time(), HELP),
+            Ok(()) => HELP.to_owned(),
+            Err(err) => format!("Simulation failed: {}", err),
+        };
+    }
+}
+
+fn format_deadline(deadline: Option<Time>) -> String {
+    deadline.map(|deadline| deadline.to_string()).unwrap_or_else(|| "-".to_owned())
+}
+
+fn draw(frame: &mut Frame, app: &App, snapshot: &Snapshot) {
+    let [header_area, body_area, footer_area] = Layout::vertical([Constraint::Length(3), Constraint::Min(0), Constraint::Length(3)]).areas(frame.area());
+    let [left_area, right_area] = Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(body_area);
+    let running: Vec<&ProcessView> = snapshot.processes.iter().filter(|process| matches!(process.state, ProcessState::Running(_))).collect();
+    let [running_area, ready_area, blocked_area] = Layout::vertical([Constraint::Length(running.len().max(1) as u16 + 3), Constraint::Min(0), Constraint::Length(3)]).areas(left_area);
+    let [statistics_area, events_area] = Layout::vertical([Constraint::Length(10), Constraint::Min(0)]).areas(right_area);
+
+    let simulated = match app.stepper.is_finished() {
+        true => format!("{} (end)", app.stepper.get_simulated_time()),
+        false => app.stepper.get_simulated_time().to_string(),
+    };
+    frame.render_widget(Paragraph::new(Line::from(vec![app.name.clone().bold(), format!("   time: {}   simulated: {}", snapshot.time, simulated).into()])).block(Block::bordered().title("Step by step simulation")), header_area);
+
+    let header = Row::new(["Id", "Core", "Priority", "Group", "Arrival", "Remaining", "Deadline"]).style(Style::new().bold());
+    frame.render_widget(Table::new(running.iter().map(|process| {
+        let core = match process.state {
+            ProcessState::Running(core) => core,
+            _ => 0,
+        };
+        Row::new([process.id.to_string(), core.to_string(), process.priority.to_string(), process.group.to_string(), process.arrival_time.to_string(), process.remaining_time.to_string(), format_deadline(process.deadline)])
+    }), [Constraint::Length(8); 7]).header(header.clone()).block(Block::bordered().title("Running")), running_area);
+
+    let ready: Vec<&ProcessView> = snapshot.processes.iter().filter(|process| process.state == ProcessState::Ready).collect();
+    frame.render_widget(Table::new(ready.iter().map(|process| Row::new([process.id.to_string(), String::new(), process.priority.to_string(), process.group.to_string(), process.arrival_time.to_string(), process.remaining_time.to_string(), format_deadline(process.deadline)])), [Constraint::Length(8); 7]).header(header).block(Block::bordered().title(format!("Ready ({})", ready.len()))), ready_area);
+
+    let blocked: Vec<String> = snapshot.processes.iter().filter(|process| process.state == ProcessState::Blocked).map(|process| format!("P{}", process.id)).collect();
+    frame.render_widget(Paragraph::new(blocked.join(" ")).block(Block::bordered().title(format!("Blocked on I/O ({})", blocked.len()))), blocked_area);
+
+    let counters = &snapshot.counters;
+    let statistics = vec![
+        Line::from(format!("Arrived processes: {}", snapshot.processes.len())),
+        Line::from(format!("Completed: {}, expired: {}, abandoned: {}", snapshot.get_count(ProcessState::Completed), snapshot.get_count(ProcessState::Expired), snapshot.get_count(ProcessState::Abandoned))),
+        Line::from(format!("Working time: {}", counters.working_time)),
+        Line::from(format!("Idle time: {}", counters.idle_time)),
+        Line::from(format!("Context switches: {}, time: {}", counters.context_switches_count, counters.context_switches_time)),
+        Line::from(format!("CPU utilization: {:.2}", counters.working_time as f64 / snapshot.time.max(1) as f64)),
+    ];
+    frame.render_widget(Paragraph::new(statistics).block(Block::bordered().title("Statistics")), statistics_area);
+
+    let events_count = events_area.height.saturating_sub(2) as usize;
+    let events = snapshot.recent_events.iter().rev().take(events_count).map(|event| match event.get_core() {
+        Some(core) => format!("{:>8}  P{} {} on core {}", event.get_time(), event.get_process_id(), event.get_kind().get_name(), core),
+        None => format!("{:>8}  P{} {}", event.get_time(), event.get_process_id(), event.get_kind().get_name()),
+    });
+    frame.render_widget(List::new(events).block(Block::bordered().title("Recent events")), events_area);
+
+    let footer = match &app.jump_input {
+        Some(input) => format!("Jump to time: {}_   (Enter - go, Esc - cancel)", input),
+        None => app.message.clone(),
+    };
+    frame.render_widget(Paragraph::new(footer).block(Block::bordered()), footer_area);
+}
+
+#[cfg(test)]
+mod tests {
+    use zad1::generator::{self, Distribution, GeneratorSettings, JobClass};
+    use zad1::registry::Registry;
+    use zad1::simulator::SimulationSettings;
+    use zad1::trace::{Slice, Trace};
+
+    use super::*;
+
+    // Short bursts and bursts of a single tick, lifetimes which expire before processes start, I/O and several groups
+    fn random_workload(seed: u64) -> Processes {
+        let settings = GeneratorSettings { minimum_processes_count: 1, maximum_processes_count: 25, inter_arrival_time: Distribution::Exponential { mean: 1f64 + (seed % 5) as f64 * 10f64 }, job_classes: vec![JobClass { share: 0.8, duration: Distribution::Uniform { minimum: 1, maximum: 60 } }, JobClass { share: 0.2, duration: Distribution::Uniform { minimum: 1, maximum: 1 } }], lifetime_probability: 0.4, lifetime: Distribution::Uniform { minimum: 0, maximum: 300 }, priority_classes_count: 4, minimum_tickets: 1, maximum_tickets: 100, maximum_io_bursts_count: (seed % 3) as usize, io_duration: Distribution::Uniform { minimum: 1, maximum: 30 }, groups_count: 3, seed: Some(seed) };
+        generator::generate(&settings).unwrap()
+    }
+
+    // Stepping tick by tick in visualizer has to give the same schedule as simulation of whole gaps between arrivals
+    #[test]
+    fn stepping_through_simulation_matches_whole_simulation() {
+        let event_key = |event: &Event| (event.get_time(), event.get_process_id(), event.get_kind().get_name(), event.get_core());
+        let slice_key = |slice: &Slice| (slice.get_start(), slice.get_end(), slice.get_process_id(), slice.get_core());
+        for seed in 0..20 {
+            let workload = random_workload(seed);
+            let settings = SimulationSettings { context_switch_cost: seed % 2, lottery_seed: Some(seed), ..SimulationSettings::default() };
+            let registry = Registry::new();
+            for name in registry.get_names() {
+                let mut cpu = registry.create(name, &settings).unwrap().unwrap();
+                cpu.enable_trace();
+                for (wait, description) in workload.iter() {
+                    cpu.simulate_n_ticks(*wait);
+                    cpu.add_process(description).unwrap();
+                }
+                cpu.simulate_till_end_of_every_process();
+                let mut stepper = Stepper::new(registry.create(name, &settings).unwrap().unwrap(), workload.clone()).unwrap();
+                stepper.go_to_next_event().unwrap();
+                stepper.go_to_end().unwrap();
+                assert!(stepper.is_finished(), "{}: seed {}", name, seed);
+                assert_eq!(stepper.cpu.get_trace().get_slices().iter().map(slice_key).collect::<Vec<_>>(), cpu.get_trace().get_slices().iter().map(slice_key).collect::<Vec<_>>(), "{}: seed {}", name, seed);
+                // Preemption is recorded only when the next process runs on its core, so order of events depends on where calls end
+                let events = |trace: &Trace| {
+                    let mut events: Vec<_> = trace.get_events().iter().map(event_key).collect();
+                    events.sort();
+                    events
+                };
+                assert_eq!(events(stepper.cpu.get_trace()), events(cpu.get_trace()), "{}: seed {}", name, seed);
+                let snapshot = stepper.get_snapshot();
+                assert_eq!(snapshot.processes.len(), workload.iter().count());
+                assert!(snapshot.processes.iter().all(|process| matches!(process.state, ProcessState::Completed | ProcessState::Expired | ProcessState::Abandoned)), "{}: seed {}", name, seed);
+                assert_eq!(snapshot.counters.working_time, cpu.get_total_working_time());
+                // Halfway at most one process runs on every core and statistics of snapshot agree with the trace
+                stepper.go_to(snapshot.time / 2).unwrap();
+                let snapshot = stepper.get_snapshot();
+                let running: Vec<_> = snapshot.processes.iter().filter_map(|process| match process.state {
+                    ProcessState::Running(core) => Some(core),
+                    _ => None,
+                }).collect();
+                assert!(running.len() <= settings.cores_count && running.iter().all(|core| *core < settings.cores_count), "{}: seed {}", name, seed);
+                let executed: Time = stepper.cpu.get_trace().get_slices().iter().filter(|slice| slice.get_start() < snapshot.time).map(|slice| slice.get_end().min(snapshot.time) - slice.get_start()).sum();
+                assert_eq!(snapshot.counters.working_time, executed, "{}: seed {}", name, seed);
+                stepper.go_to_previous_event();
+                assert!(stepper.get_position() < snapshot.time || snapshot.time == 0);
+            }
+        }
+    }
+}
@@ -0,0 +1,223 @@
+// Workload files keep the same data which is fed into Processes, one process per entry, in order of arrival.
+//
+// CSV format (any extension other than .json):
+//   wait,duration,lifetime,priority,io_bursts,tickets,group,period
+//   0,25,,0,,
+//   12,3,40,2,10:5;4:7,50,1,
+//   0,5,20,0,,100,0,20
+// - wait - time between previous process arrival (or start of simulation) and this one
+// - duration - required processor time, cannot be 0
+// - lifetime - optional, empty field means no lifetime
+// - priority - optional, 0 if omitted (0 - the most important)
+// - io_bursts - optional, ';' separated pairs "I/O burst:processor burst" which follow the first processor burst
+// - tickets - optional, requested processor share for lottery and stride scheduling, 100 if omitted, cannot be 0
+// - group - optional, owner of the process for fair share scheduling, 0 if omitted
+// - period - optional, period of the task for jobs of periodic tasks, empty field means no period, cannot be 0
+// Empty lines and lines starting with '#' are ignored, header line is optional.
+//
+// JSON format (.json extension) is an array of objects with the same fields:
+//   [{"wait": 0, "duration": 25, "lifetime": null, "priority": 0, "io_bursts": [[10, 5], [4, 7]], "tickets": 50, "group": 1, "period": null}]
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{cpu_access_manager::{ProcessDescription, Time, DEFAULT_TICKETS}, simulator::Processes};
+
+#[derive(Debug)]
+pub enum WorkloadError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Parse { line: usize, message: String },
+    InvalidEntry { entry: usize, message: String }, // Position in JSON array, counted from 1
+}
+
+impl std::fmt::Display for WorkloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkloadError::Io(err) => write!(f, "{}", err),
+            WorkloadError::Json(err) => write!(f, "Invalid JSON: {}", err),
+            WorkloadError::Parse { line, message } => write!(f, "Line {}: {}", line, message),
+            WorkloadError::InvalidEntry { entry, message } => write!(f, "Entry {}: {}", entry, message),
+        }
+    }
+}
+
+impl From<std::io::Error> for WorkloadError {
+    fn from(value: std::io::Error) -> Self {
+        WorkloadError::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for WorkloadError {
+    fn from(value: serde_json::Error) -> Self {
+        WorkloadError::Json(value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WorkloadEntry {
+    wait: Time,
+    duration: Time,
+    #[serde(default)]
+    lifetime: Option<Time>,
+    #[serde(default)]
+    priority: u32,
+    #[serde(default)]
+    io_bursts: Vec<(Time, Time)>,
+    #[serde(default = "default_tickets")]
+    tickets: u32,
+    #[serde(default)]
+    group: u32,
+    #[serde(default)]
+    period: Option<Time>,
+}
+
+#[inline]
+fn default_tickets() -> u32 {
+    DEFAULT_TICKETS
+}
+
+impl WorkloadEntry {
+    #[inline]
+    fn to_description(&self) -> ProcessDescription {
+        let description = ProcessDescription::new(self.duration, self.lifetime, self.priority).with_io_bursts(self.io_bursts.clone()).with_tickets(self.tickets).with_group(self.group);
+        match self.period {
+            Some(period) => description.with_period(period),
+            None => description,
+        }
+    }
+
+    #[inline]
+    fn validate(&self) -> Result<(), String> {
+        self.to_description().validate().map_err(|err| err.to_string())
+    }
+}
+
+#[inline]
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("json"))
+}
+
+fn parse_field<T: std::str::FromStr>(field: Option<&str>, name: &str, line: usize) -> Result<Option<T>, WorkloadError> {
+    match field.map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(value) => value.parse().map(Some).map_err(|_| WorkloadError::Parse { line, message: format!("Invalid {}: {}", name, value) }),
+    }
+}
+
+fn parse_io_bursts(field: Option<&str>, line: usize) -> Result<Vec<(Time, Time)>, WorkloadError> {
+    let mut ans = Vec::new();
+    for burst in field.map(str::trim).unwrap_or_default().split(';').filter(|burst| !burst.trim().is_empty()) {
+        let (io_time, duration) = burst.split_once(':').ok_or(WorkloadError::Parse { line, message: format!("Invalid I/O burst: {}", burst) })?;
+        ans.push((parse_field(Some(io_time), "I/O burst", line)?.unwrap_or(0), parse_field(Some(duration), "processor burst", line)?.unwrap_or(0)));
+    }
+    Ok(ans)
+}
+
+pub fn to_csv(processes: &Processes) -> String {
+    let mut ans = String::from("wait,duration,lifetime,priority,io_bursts,tickets,group,period\n");
+    for (wait, description) in processes.iter() {
+        let io_bursts = description.get_io_bursts().iter().map(|(io_time, duration)| format!("{}:{}", io_time, duration)).collect::<Vec<_>>().join(";");
+        ans.push_str(&format!("{},{},{},{},{},{},{},{}\n", wait, description.get_duration(), description.get_lifetime().map(|lifetime| lifetime.to_string()).unwrap_or_default(), description.get_priority(), io_bursts, description.get_tickets(), description.get_group(), description.get_period().map(|period| period.to_string()).unwrap_or_default()));
+    }
+    ans
+}
+
+pub fn from_csv(content: &str) -> Result<Processes, WorkloadError> {
+    let mut entries = Vec::new();
+    for (index, row) in content.lines().enumerate() {
+        let line = index + 1;
+        let row = row.trim();
+        if row.is_empty() || row.starts_with('#') || (entries.is_empty() && row.starts_with("wait")) {
+            continue;
+        }
+        let mut fields = row.split(',');
+        let entry = WorkloadEntry {
+            wait: parse_field(fields.next(), "wait", line)?.ok_or(WorkloadError::Parse { line, message: "Missing wait".to_owned() })?,
+            duration: parse_field(fields.next(), "duration", line)?.ok_or(WorkloadError::Parse { line, message: "Missing duration".to_owned() })?,
+            lifetime: parse_field(fields.next(), "lifetime", line)?,
+            priority: parse_field(fields.next(), "priority", line)?.unwrap_or(0),
+            io_bursts: parse_io_bursts(fields.next(), line)?,
+            tickets: parse_field(fields.next(), "tickets", line)?.unwrap_or(DEFAULT_TICKETS),
+            group: parse_field(fields.next(), "group", line)?.unwrap_or(0),
+            period: parse_field(fields.next(), "period", line)?,
+        };
+        entry.validate().map_err(|message| WorkloadError::Parse { line, message })?;
+        entries.push(entry);
+    }
+    Ok(from_entries(entries))
+}
+
+pub fn to_json(processes: &Processes) -> Result<String, WorkloadError> {
+    let entries: Vec<WorkloadEntry> = processes.iter().map(|(wait, description)| WorkloadEntry { wait: *wait, duration: description.get_duration(), lifetime: description.get_lifetime(), priority: description.get_priority(), io_bursts: description.get_io_bursts().to_vec(), tickets: description.get_tickets(), group: description.get_group(), period: description.get_period() }).collect();
+    Ok(serde_json::to_string_pretty(&entries)?)
+}
+
+pub fn from_json(content: &str) -> Result<Processes, WorkloadError> {
+    let entries: Vec<WorkloadEntry> = serde_json::from_str(content)?;
+    for (index, entry) in entries.iter().enumerate() {
+        entry.validate().map_err(|message| WorkloadError::InvalidEntry { entry: index + 1, message })?;
+    }
+    Ok(from_entries(entries))
+}
+
+fn from_entries(entries: Vec<WorkloadEntry>) -> Processes {
+    Processes::from(entries.iter().map(|entry| (entry.wait, entry.to_description())).collect::<Vec<_>>())
+}
+
+pub fn save<P: AsRef<Path>>(processes: &Processes, path: P) -> Result<(), WorkloadError> {
+    let content = match is_json(path.as_ref()) {
+        true => to_json(processes)?,
+        false => to_csv(processes),
+    };
+    Ok(std::fs::write(path, content)?)
+}
+
+pub fn load<P: AsRef<Path>>(path: P) -> Result<Processes, WorkloadError> {
+    let content = std::fs::read_to_string(path.as_ref())?;
+    match is_json(path.as_ref()) {
+        true => from_json(&content),
+        false => from_csv(&content),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Fields = (Time, Time, Option<Time>, u32, Vec<(Time, Time)>, u32, u32, Option<Time>);
+
+    fn workload() -> Processes {
+        Processes::from(vec![(0, ProcessDescription::new(25, None, 0)), (12, ProcessDescription::new(3, Some(40), 2).with_io_bursts(vec![(10, 5), (4, 7)]).with_tickets(50).with_group(1)), (0, ProcessDescription::new(5, Some(20), 0).with_period(20))])
+    }
+
+    fn fields(processes: &Processes) -> Vec<Fields> {
+        processes.iter().map(|(wait, description)| (*wait, description.get_duration(), description.get_lifetime(), description.get_priority(), description.get_io_bursts().to_vec(), description.get_tickets(), description.get_group(), description.get_period())).collect()
+    }
+
+    #[test]
+    fn csv_format() {
+        let csv = to_csv(&workload());
+        assert_eq!(csv, "wait,duration,lifetime,priority,io_bursts,tickets,group,period\n0,25,,0,,100,0,\n12,3,40,2,10:5;4:7,50,1,\n0,5,20,0,,100,0,20\n");
+        assert_eq!(fields(&from_csv("# comment\n\n0,25\n12,3,40,2,10:5;4:7,50,1").unwrap()), fields(&workload())[..2].to_vec());
+    }
+
+    // Replay of saved workload has to give the same run, so every field of every process survives saving and loading
+    #[test]
+    fn save_and_load_keep_every_field() {
+        for extension in ["csv", "json"] {
+            let path = std::env::temp_dir().join(format!("zad1_workload_{}.{}", std::process::id(), extension));
+            save(&workload(), &path).unwrap();
+            let loaded = load(&path);
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(fields(&loaded.unwrap()), fields(&workload()), "{}", extension);
+        }
+    }
+
+    #[test]
+    fn invalid_process_is_located() {
+        assert_eq!(from_csv("wait,duration\n0,5\n\n3,0").unwrap_err().to_string(), "Line 4: Process can't have duration time of 0");
+        assert_eq!(from_json("[\n{\"wait\": 0, \"duration\": 5},\n{\"wait\": 3, \"duration\": 0}\n]").unwrap_err().to_string(), "Entry 2: Process can't have duration time of 0");
+    }
+}